@@ -0,0 +1,438 @@
+//! Attribute macro for [`detrim`](https://docs.rs/detrim).
+//!
+//! This crate is not intended to be used directly. Enable the `derive` feature of `detrim` and use
+//! `detrim::detrim` instead.
+
+mod trim;
+
+use std::mem;
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Field, Fields, GenericArgument,
+    PathArguments, Type,
+};
+
+/// Trims every string field of a struct or enum during deserialization.
+///
+/// Fields are given the matching `deserialize_with` attribute based on their type:
+///
+/// | Field type       | Deserializer              | With `#[detrim(non_empty)]`                   |
+/// |------------------|---------------------------|-----------------------------------------------|
+/// | `String`         | `detrim::string`          | `detrim::string_non_empty`                    |
+/// | `Option<String>` | `detrim::option_string`   | `detrim::option_string_non_empty`             |
+/// | `Vec<String>`    | `detrim::vec_string`      | `detrim::seq::NonEmpty::<false>::vec_string`  |
+/// | `Cow<'a, str>`   | `detrim::cow_str`         | -                                             |
+///
+/// `Option<String>` fields also get `#[serde(default)]` so that missing fields are still `None`,
+/// and `Cow<'a, str>` fields get `#[serde(borrow)]`. Fields of other types, and fields that already
+/// have a serde `with` or `deserialize_with` attribute, are left unchanged. Use `#[detrim(skip)]`
+/// to leave a string field untrimmed.
+///
+/// The attribute must be placed above `#[derive(Deserialize)]`, otherwise the derive will not see
+/// the added attributes.
+///
+/// # Examples
+///
+/// ```
+/// #[detrim::detrim]
+/// #[derive(Debug, serde::Deserialize)]
+/// struct Form {
+///     name: String,
+///     #[detrim(non_empty)]
+///     email: String,
+///     nickname: Option<String>,
+///     #[detrim(skip)]
+///     password: String,
+/// }
+///
+/// let form = serde_json::from_str::<Form>(
+///     r#"{ "name": " ferris ", "email": " ferris@example.com ", "password": " hunter2 " }"#,
+/// )
+/// .unwrap();
+/// assert_eq!(form.name, "ferris");
+/// assert_eq!(form.email, "ferris@example.com");
+/// assert_eq!(form.nickname, None);
+/// assert_eq!(form.password, " hunter2 ");
+///
+/// serde_json::from_str::<Form>(r#"{ "name": "", "email": " ", "password": "" }"#).unwrap_err();
+/// ```
+#[proc_macro_attribute]
+pub fn detrim(
+    args: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    match syn::parse(item) {
+        Ok(input) => expand(args.into(), input).into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
+/// Derives `detrim::Trim` by trimming every field in place.
+///
+/// Each field's type must implement `Trim`, unless the field is marked with `#[trim(skip)]`. Type
+/// parameters are given a `Trim` bound.
+///
+/// # Examples
+///
+/// ```
+/// use detrim::Trim;
+///
+/// #[derive(Debug, Trim)]
+/// struct User {
+///     name: String,
+///     emails: Vec<String>,
+///     age: u32,
+///     #[trim(skip)]
+///     password: String,
+/// }
+///
+/// let mut user = User {
+///     name: " ferris ".to_owned(),
+///     emails: vec![" ferris@example.com ".to_owned()],
+///     age: 7,
+///     password: " hunter2 ".to_owned(),
+/// };
+/// user.trim_in_place();
+///
+/// assert_eq!(user.name, "ferris");
+/// assert_eq!(user.emails, ["ferris@example.com"]);
+/// assert_eq!(user.password, " hunter2 ");
+/// ```
+#[proc_macro_derive(Trim, attributes(trim))]
+pub fn derive_trim(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    trim::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(args: TokenStream, mut input: DeriveInput) -> TokenStream {
+    let mut errors = Vec::new();
+
+    if !args.is_empty() {
+        errors.push(syn::Error::new_spanned(
+            args,
+            "`#[detrim]` does not take arguments",
+        ));
+    }
+
+    match &mut input.data {
+        Data::Struct(data) => rewrite_fields(&mut data.fields, &mut errors),
+        Data::Enum(data) => {
+            for variant in &mut data.variants {
+                rewrite_fields(&mut variant.fields, &mut errors);
+            }
+        }
+        Data::Union(data) => errors.push(syn::Error::new(
+            data.union_token.span,
+            "`#[detrim]` does not support unions",
+        )),
+    }
+
+    let errors = errors.into_iter().map(syn::Error::into_compile_error);
+
+    quote! {
+        #input
+        #(#errors)*
+    }
+}
+
+fn rewrite_fields(fields: &mut Fields, errors: &mut Vec<syn::Error>) {
+    for field in fields.iter_mut() {
+        if let Err(err) = rewrite_field(field) {
+            errors.push(err);
+        }
+    }
+}
+
+/// Adds the matching serde attributes to `field`, removing any `#[detrim(...)]` attributes.
+fn rewrite_field(field: &mut Field) -> syn::Result<()> {
+    let opts = FieldOpts::take_from(field)?;
+
+    if opts.skip {
+        return Ok(());
+    }
+
+    let Some(kind) = Kind::of(&field.ty) else {
+        if opts.non_empty {
+            return Err(syn::Error::new_spanned(
+                &field.ty,
+                "`#[detrim(non_empty)]` is only supported on `String`, `Option<String>`, and \
+                 `Vec<String>` fields",
+            ));
+        }
+
+        return Ok(());
+    };
+
+    if has_serde_key(field, "with") || has_serde_key(field, "deserialize_with") {
+        if opts.non_empty {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`#[detrim(non_empty)]` conflicts with serde's `with` and `deserialize_with` \
+                 attributes",
+            ));
+        }
+
+        return Ok(());
+    }
+
+    let path = match (kind, opts.non_empty) {
+        (Kind::String, false) => "::detrim::string",
+        (Kind::String, true) => "::detrim::string_non_empty",
+        (Kind::OptionString, false) => "::detrim::option_string",
+        (Kind::OptionString, true) => "::detrim::option_string_non_empty",
+        (Kind::VecString, false) => "::detrim::vec_string",
+        (Kind::VecString, true) => "::detrim::seq::NonEmpty::<false>::vec_string",
+        (Kind::CowStr, false) => "::detrim::cow_str",
+        (Kind::CowStr, true) => {
+            return Err(syn::Error::new_spanned(
+                &field.ty,
+                "`#[detrim(non_empty)]` is not supported on `Cow<str>` fields",
+            ));
+        }
+        (Kind::CowStaticStr, _) => {
+            return Err(syn::Error::new_spanned(
+                &field.ty,
+                "`Cow<'static, str>` fields cannot borrow from the input; use `String` or add \
+                 `#[detrim(skip)]`",
+            ));
+        }
+    };
+
+    field
+        .attrs
+        .push(parse_quote!(#[serde(deserialize_with = #path)]));
+
+    match kind {
+        Kind::OptionString if !has_serde_key(field, "default") => {
+            field.attrs.push(parse_quote!(#[serde(default)]));
+        }
+        Kind::CowStr if !has_serde_key(field, "borrow") => {
+            field.attrs.push(parse_quote!(#[serde(borrow)]));
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Options from `#[detrim(...)]` field attributes.
+#[derive(Debug, Default)]
+struct FieldOpts {
+    skip: bool,
+    non_empty: bool,
+}
+
+impl FieldOpts {
+    /// Parses and removes all `#[detrim(...)]` attributes from `field`.
+    fn take_from(field: &mut Field) -> syn::Result<Self> {
+        let (detrim_attrs, attrs) = mem::take(&mut field.attrs)
+            .into_iter()
+            .partition::<Vec<_>, _>(|attr| attr.path().is_ident("detrim"));
+        field.attrs = attrs;
+
+        let mut opts = Self::default();
+
+        for attr in &detrim_attrs {
+            attr.parse_nested_meta(|meta| {
+                let opt = if meta.path.is_ident("skip") {
+                    &mut opts.skip
+                } else if meta.path.is_ident("non_empty") {
+                    &mut opts.non_empty
+                } else {
+                    return Err(meta.error("unknown detrim option, expected `skip` or `non_empty`"));
+                };
+
+                if *opt {
+                    return Err(meta.error("duplicate detrim option"));
+                }
+
+                *opt = true;
+                Ok(())
+            })?;
+        }
+
+        if opts.skip && opts.non_empty {
+            return Err(syn::Error::new_spanned(
+                &detrim_attrs[0],
+                "`skip` and `non_empty` cannot be used together",
+            ));
+        }
+
+        Ok(opts)
+    }
+}
+
+/// Field types that have a matching detrim deserializer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    String,
+    OptionString,
+    VecString,
+    CowStr,
+    CowStaticStr,
+}
+
+impl Kind {
+    fn of(ty: &Type) -> Option<Self> {
+        let (ident, args) = last_segment(ty)?;
+
+        match (ident.as_str(), args.as_slice()) {
+            ("String", []) => Some(Self::String),
+            ("Option", [GenericArgument::Type(ty)]) if is_path(ty, "String") => {
+                Some(Self::OptionString)
+            }
+            ("Vec", [GenericArgument::Type(ty)]) if is_path(ty, "String") => Some(Self::VecString),
+            ("Cow", [GenericArgument::Lifetime(lt), GenericArgument::Type(ty)])
+                if is_path(ty, "str") =>
+            {
+                if lt.ident == "static" {
+                    Some(Self::CowStaticStr)
+                } else {
+                    Some(Self::CowStr)
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns the name and generic arguments of the last segment of a type path.
+fn last_segment(mut ty: &Type) -> Option<(String, Vec<GenericArgument>)> {
+    // types passed through `macro_rules!` can be wrapped in invisible groups
+    while let Type::Group(group) = ty {
+        ty = &group.elem;
+    }
+
+    let Type::Path(path) = ty else {
+        return None;
+    };
+
+    if path.qself.is_some() {
+        return None;
+    }
+
+    let segment = path.path.segments.last()?;
+
+    let args = match &segment.arguments {
+        PathArguments::None => Vec::new(),
+        PathArguments::AngleBracketed(args) => args.args.iter().cloned().collect(),
+        PathArguments::Parenthesized(_) => return None,
+    };
+
+    Some((segment.ident.to_string(), args))
+}
+
+/// Returns true if `ty` is a path ending in `name` with no generic arguments.
+fn is_path(ty: &Type, name: &str) -> bool {
+    matches!(last_segment(ty), Some((ident, args)) if ident == name && args.is_empty())
+}
+
+/// Returns true if any `#[serde(...)]` attribute on `field` contains the top-level key `name`.
+fn has_serde_key(field: &Field, name: &str) -> bool {
+    field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+        .filter_map(|attr| attr.meta.require_list().ok())
+        .flat_map(|list| list.tokens.clone())
+        .any(|token| matches!(token, TokenTree::Ident(ident) if ident == name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Expands `item` and returns the compile error messages.
+    fn errors(item: DeriveInput) -> Vec<String> {
+        expand(TokenStream::new(), item)
+            .into_iter()
+            .filter_map(|token| match token {
+                TokenTree::Group(group) => {
+                    syn::parse2::<syn::LitStr>(group.stream().into_iter().next()?.into())
+                        .ok()
+                        .map(|lit| lit.value())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn kinds() {
+        assert_eq!(Kind::of(&parse_quote!(String)), Some(Kind::String));
+        assert_eq!(
+            Kind::of(&parse_quote!(std::string::String)),
+            Some(Kind::String),
+        );
+        assert_eq!(
+            Kind::of(&parse_quote!(Option<String>)),
+            Some(Kind::OptionString),
+        );
+        assert_eq!(
+            Kind::of(&parse_quote!(Vec<alloc::string::String>)),
+            Some(Kind::VecString),
+        );
+        assert_eq!(Kind::of(&parse_quote!(Cow<'a, str>)), Some(Kind::CowStr));
+        assert_eq!(
+            Kind::of(&parse_quote!(Cow<'static, str>)),
+            Some(Kind::CowStaticStr),
+        );
+
+        assert_eq!(Kind::of(&parse_quote!(u32)), None);
+        assert_eq!(Kind::of(&parse_quote!(&'a str)), None);
+        assert_eq!(Kind::of(&parse_quote!(Option<u32>)), None);
+        assert_eq!(Kind::of(&parse_quote!(Vec<Vec<String>>)), None);
+        assert_eq!(Kind::of(&parse_quote!(Cow<'a, [u8]>)), None);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(errors(parse_quote! { struct Foo { a: String } }).is_empty());
+
+        assert_eq!(
+            errors(parse_quote! { struct Foo { #[detrim(trim)] a: String } }),
+            ["unknown detrim option, expected `skip` or `non_empty`"],
+        );
+        assert_eq!(
+            errors(parse_quote! { struct Foo { #[detrim(skip, skip)] a: String } }),
+            ["duplicate detrim option"],
+        );
+        assert_eq!(
+            errors(parse_quote! { struct Foo { #[detrim(skip, non_empty)] a: String } }),
+            ["`skip` and `non_empty` cannot be used together"],
+        );
+        assert_eq!(
+            errors(parse_quote! { struct Foo { #[detrim(non_empty)] a: u32 } }).len(),
+            1,
+        );
+        assert_eq!(
+            errors(parse_quote! { struct Foo<'a> { #[detrim(non_empty)] a: Cow<'a, str> } }),
+            ["`#[detrim(non_empty)]` is not supported on `Cow<str>` fields"],
+        );
+        assert_eq!(
+            errors(parse_quote! { struct Foo { a: Cow<'static, str>, b: Cow<'static, str> } })
+                .len(),
+            2,
+        );
+        assert_eq!(
+            errors(parse_quote! {
+                struct Foo {
+                    #[detrim(non_empty)]
+                    #[serde(with = "bar")]
+                    a: String,
+                }
+            })
+            .len(),
+            1,
+        );
+        assert_eq!(
+            errors(parse_quote! { union Foo { a: u32 } }),
+            ["`#[detrim]` does not support unions"],
+        );
+    }
+}
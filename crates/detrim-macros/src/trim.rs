@@ -0,0 +1,143 @@
+//! Implementation of `#[derive(Trim)]`.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_quote, Data, DeriveInput, Field, Fields};
+
+pub(crate) fn expand(mut input: DeriveInput) -> syn::Result<TokenStream> {
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let (pattern, stmts) = destructure(quote!(Self), &data.fields)?;
+
+            if stmts.is_empty() {
+                TokenStream::new()
+            } else {
+                quote! {
+                    let #pattern = self;
+                    #(#stmts)*
+                }
+            }
+        }
+
+        // `self` can not be matched directly since references to uninhabited types are inhabited
+        Data::Enum(data) if data.variants.is_empty() => quote! { match *self {} },
+
+        Data::Enum(data) => {
+            let arms = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let ident = &variant.ident;
+                    let (pattern, stmts) = destructure(quote!(Self::#ident), &variant.fields)?;
+                    Ok(quote! { #pattern => { #(#stmts)* } })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+
+        Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span,
+                "`Trim` cannot be derived for unions",
+            ));
+        }
+    };
+
+    for param in input.generics.type_params_mut() {
+        param.bounds.push(parse_quote!(::detrim::Trim));
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics ::detrim::Trim for #ident #ty_generics #where_clause {
+            fn trim_in_place(&mut self) {
+                #body
+            }
+        }
+    })
+}
+
+/// Returns a pattern binding the non-skipped fields, and statements that trim each of them.
+fn destructure(path: TokenStream, fields: &Fields) -> syn::Result<(TokenStream, Vec<TokenStream>)> {
+    let mut elems = Vec::new();
+    let mut stmts = Vec::new();
+
+    for (idx, field) in fields.iter().enumerate() {
+        let binding = format_ident!("__field{idx}");
+
+        if is_skipped(field)? {
+            // skipped named fields are covered by `..`
+            if field.ident.is_none() {
+                elems.push(quote!(_));
+            }
+
+            continue;
+        }
+
+        elems.push(match &field.ident {
+            Some(ident) => quote!(#ident: #binding),
+            None => quote!(#binding),
+        });
+        stmts.push(quote! { ::detrim::Trim::trim_in_place(#binding); });
+    }
+
+    let pattern = match fields {
+        Fields::Named(_) => quote!(#path { #(#elems,)* .. }),
+        Fields::Unnamed(_) => quote!(#path(#(#elems),*)),
+        Fields::Unit => path,
+    };
+
+    Ok((pattern, stmts))
+}
+
+/// Returns true if field is marked with `#[trim(skip)]`.
+fn is_skipped(field: &Field) -> syn::Result<bool> {
+    let mut skip = false;
+
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("trim"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident("skip") {
+                return Err(meta.error("unknown trim option, expected `skip`"));
+            }
+
+            if skip {
+                return Err(meta.error("duplicate trim option"));
+            }
+
+            skip = true;
+            Ok(())
+        })?;
+    }
+
+    Ok(skip)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid() {
+        let err = expand(parse_quote! { struct Foo { #[trim(all)] a: String } }).unwrap_err();
+        assert_eq!(err.to_string(), "unknown trim option, expected `skip`");
+
+        let err =
+            expand(parse_quote! { struct Foo { #[trim(skip, skip)] a: String } }).unwrap_err();
+        assert_eq!(err.to_string(), "duplicate trim option");
+
+        let err = expand(parse_quote! { union Foo { a: u32 } }).unwrap_err();
+        assert_eq!(err.to_string(), "`Trim` cannot be derived for unions");
+    }
+}
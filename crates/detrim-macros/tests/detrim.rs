@@ -0,0 +1,67 @@
+//! Deserialization tests for the `#[detrim]` attribute.
+
+use std::borrow::Cow;
+
+use serde::Deserialize;
+
+#[test]
+fn rewrite() {
+    #[detrim::detrim]
+    #[derive(Debug, Deserialize, PartialEq, Eq)]
+    struct Foo<'a> {
+        a: String,
+        b: Option<String>,
+        c: Vec<String>,
+        d: Cow<'a, str>,
+        #[detrim(skip)]
+        e: String,
+        #[serde(deserialize_with = "detrim::option_str")]
+        f: Option<&'a str>,
+        g: u32,
+    }
+
+    let json = r#"{ "a": " a ", "c": [" c "], "d": "d ", "e": " e ", "f": "f ", "g": 1 }"#;
+    let foo = serde_json::from_str::<Foo<'_>>(json).unwrap();
+    assert_eq!(
+        foo,
+        Foo {
+            a: "a".to_owned(),
+            b: None,
+            c: vec!["c".to_owned()],
+            d: Cow::Borrowed("d"),
+            e: " e ".to_owned(),
+            f: Some("f"),
+            g: 1,
+        },
+    );
+}
+
+#[test]
+fn rewrite_non_empty() {
+    #[detrim::detrim]
+    #[derive(Debug, Deserialize, PartialEq, Eq)]
+    enum Foo {
+        Bar(#[detrim(non_empty)] String),
+        Baz {
+            #[detrim(non_empty)]
+            baz: Option<String>,
+            #[detrim(non_empty)]
+            qux: Vec<String>,
+        },
+    }
+
+    serde_json::from_str::<Foo>(r#"{ "Bar": " " }"#).unwrap_err();
+    serde_json::from_str::<Foo>(r#"{ "Baz": { "qux": [" "] } }"#).unwrap_err();
+
+    assert_eq!(
+        serde_json::from_str::<Foo>(r#"{ "Bar": " bar " }"#).unwrap(),
+        Foo::Bar("bar".to_owned()),
+    );
+    assert_eq!(
+        serde_json::from_str::<Foo>(r#"{ "Baz": { "baz": " ", "qux": [" qux "] } }"#).unwrap(),
+        Foo::Baz {
+            baz: None,
+            qux: vec!["qux".to_owned()],
+        },
+    );
+}
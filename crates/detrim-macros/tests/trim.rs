@@ -0,0 +1,89 @@
+//! Tests for `#[derive(Trim)]`.
+
+use std::borrow::Cow;
+
+use detrim::Trim;
+
+#[test]
+fn derive_struct() {
+    #[derive(Debug, Trim, PartialEq, Eq)]
+    struct Foo<'a, T> {
+        a: String,
+        b: Option<Cow<'a, str>>,
+        c: Vec<T>,
+        #[trim(skip)]
+        d: String,
+        e: u32,
+    }
+
+    #[derive(Debug, Trim, PartialEq, Eq)]
+    struct Bar(String, #[trim(skip)] String, Foo<'static, (String, u8)>);
+
+    #[derive(Debug, Trim, PartialEq, Eq)]
+    struct Unit;
+
+    let mut bar = Bar(
+        " a ".to_owned(),
+        " b ".to_owned(),
+        Foo {
+            a: " a ".to_owned(),
+            b: Some(Cow::Borrowed(" b ")),
+            c: vec![(" c ".to_owned(), 1)],
+            d: " d ".to_owned(),
+            e: 1,
+        },
+    );
+    bar.trim_in_place();
+    Unit.trim_in_place();
+
+    assert_eq!(
+        bar,
+        Bar(
+            "a".to_owned(),
+            " b ".to_owned(),
+            Foo {
+                a: "a".to_owned(),
+                b: Some(Cow::Borrowed("b")),
+                c: vec![("c".to_owned(), 1)],
+                d: " d ".to_owned(),
+                e: 1,
+            },
+        ),
+    );
+}
+
+#[test]
+fn derive_enum() {
+    #[derive(Debug, Trim, PartialEq, Eq)]
+    enum Foo {
+        A,
+        B(String, #[trim(skip)] String),
+        C {
+            #[trim(skip)]
+            c: String,
+            d: Box<Foo>,
+        },
+    }
+
+    // only checks that the derive compiles
+    #[allow(dead_code)]
+    #[derive(Debug, Trim)]
+    enum Never {}
+
+    let mut foo = Foo::C {
+        c: " c ".to_owned(),
+        d: Box::new(Foo::B(" b ".to_owned(), " b ".to_owned())),
+    };
+    foo.trim_in_place();
+    assert_eq!(
+        foo,
+        Foo::C {
+            c: " c ".to_owned(),
+            d: Box::new(Foo::B("b".to_owned(), " b ".to_owned())),
+        },
+    );
+
+    let mut foo = Foo::A;
+    foo.trim_in_place();
+    assert_eq!(foo, Foo::A);
+}
@@ -0,0 +1,182 @@
+//! Compares streaming collection deserializers against deserializing an intermediate `Vec`.
+//!
+//! Besides timings, the peak heap usage and number of allocations of a single run of each path
+//! are printed, as measured by a counting global allocator.
+
+#![allow(missing_docs)]
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    collections::HashSet,
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use serde::{Deserialize, Deserializer};
+
+/// Global allocator that tracks current and peak heap usage, and the number of allocations.
+struct CountingAlloc;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+impl CountingAlloc {
+    fn grow(size: usize) {
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn shrink(size: usize) {
+        CURRENT.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+
+        if !ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            Self::grow(layout.size());
+        }
+
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::shrink(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+
+        if !new_ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            Self::shrink(layout.size());
+            Self::grow(new_size);
+        }
+
+        new_ptr
+    }
+}
+
+/// Runs `f` once, printing the peak heap usage above the starting point and the number of
+/// allocations (including reallocations) made.
+fn report_allocations<T>(name: &str, f: impl FnOnce() -> T) {
+    let start = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(start, Ordering::Relaxed);
+    ALLOCATIONS.store(0, Ordering::Relaxed);
+
+    let out = f();
+    let peak = PEAK.load(Ordering::Relaxed) - start;
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    drop(out);
+
+    println!("{name}: peak {peak} bytes, {allocations} allocations");
+}
+
+/// Previous implementation: deserialize a whole `Vec`, then re-allocate each trimmed item.
+fn intermediate_vec_string<'a, D: Deserializer<'a>>(de: D) -> Result<Vec<String>, D::Error> {
+    let mut list = Vec::<String>::deserialize(de)?;
+
+    for item in &mut list {
+        *item = item.trim().to_owned();
+    }
+
+    Ok(list)
+}
+
+/// Previous implementation: deserialize a whole `Vec`, re-allocate each trimmed item, then
+/// collect into a set.
+fn intermediate_hashset_string<'a, D: Deserializer<'a>>(
+    de: D,
+) -> Result<HashSet<String>, D::Error> {
+    let list = Vec::<String>::deserialize(de)?;
+    Ok(list
+        .into_iter()
+        .map(|item| item.trim().to_owned())
+        .collect())
+}
+
+fn payload(len: usize) -> String {
+    let tags = (0..len)
+        .map(|idx| format!(r#""  tag-{}  ""#, idx % (len / 2 + 1)))
+        .collect::<Vec<_>>();
+
+    format!("[{}]", tags.join(","))
+}
+
+fn vec_string(c: &mut Criterion) {
+    let mut group = c.benchmark_group("vec_string");
+
+    for len in [100, 100_000] {
+        let json = payload(len);
+
+        report_allocations(&format!("vec_string/intermediate/{len}"), || {
+            let mut de = serde_json::Deserializer::from_str(&json);
+            intermediate_vec_string(&mut de).unwrap()
+        });
+        report_allocations(&format!("vec_string/streaming/{len}"), || {
+            let mut de = serde_json::Deserializer::from_str(&json);
+            detrim::vec_string(&mut de).unwrap()
+        });
+
+        group.bench_with_input(BenchmarkId::new("intermediate", len), &json, |b, json| {
+            b.iter(|| {
+                let mut de = serde_json::Deserializer::from_str(json);
+                black_box(intermediate_vec_string(&mut de).unwrap())
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("streaming", len), &json, |b, json| {
+            b.iter(|| {
+                let mut de = serde_json::Deserializer::from_str(json);
+                black_box(detrim::vec_string(&mut de).unwrap())
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn hashset_string(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hashset_string");
+
+    for len in [100, 100_000] {
+        let json = payload(len);
+
+        report_allocations(&format!("hashset_string/intermediate/{len}"), || {
+            let mut de = serde_json::Deserializer::from_str(&json);
+            intermediate_hashset_string(&mut de).unwrap()
+        });
+        report_allocations(&format!("hashset_string/streaming/{len}"), || {
+            let mut de = serde_json::Deserializer::from_str(&json);
+            detrim::hashset_string(&mut de).unwrap()
+        });
+
+        group.bench_with_input(BenchmarkId::new("intermediate", len), &json, |b, json| {
+            b.iter(|| {
+                let mut de = serde_json::Deserializer::from_str(json);
+                black_box(intermediate_hashset_string(&mut de).unwrap())
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("streaming", len), &json, |b, json| {
+            b.iter(|| {
+                let mut de = serde_json::Deserializer::from_str(json);
+                black_box(detrim::hashset_string(&mut de).unwrap())
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, vec_string, hashset_string);
+criterion_main!(benches);
@@ -0,0 +1,49 @@
+//! Compares interning deserialization of repetitive payloads against `vec_string`.
+
+#![allow(missing_docs)]
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use detrim::intern::{StringInterner, VecArcStrSeed};
+use serde::de::DeserializeSeed as _;
+
+const STATUSES: [&str; 4] = ["active", " pending", "closed ", "  archived  "];
+
+fn payload(len: usize) -> String {
+    let items = (0..len)
+        .map(|idx| format!("{:?}", STATUSES[idx % STATUSES.len()]))
+        .collect::<Vec<_>>();
+
+    format!("[{}]", items.join(","))
+}
+
+fn intern(c: &mut Criterion) {
+    let mut group = c.benchmark_group("intern");
+    group.sample_size(10);
+
+    let len = 1_000_000;
+    let json = payload(len);
+
+    group.bench_with_input(BenchmarkId::new("vec_string", len), &json, |b, json| {
+        b.iter(|| {
+            let mut de = serde_json::Deserializer::from_str(json);
+            black_box(detrim::vec_string(&mut de).unwrap())
+        })
+    });
+
+    group.bench_with_input(BenchmarkId::new("vec_arc_str", len), &json, |b, json| {
+        let interner = StringInterner::new();
+
+        b.iter(|| {
+            let mut de = serde_json::Deserializer::from_str(json);
+            let seed = VecArcStrSeed::new(&interner);
+            black_box(seed.deserialize(&mut de).unwrap())
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, intern);
+criterion_main!(benches);
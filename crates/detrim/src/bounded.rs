@@ -0,0 +1,476 @@
+//! Trimming deserializers that enforce length bounds on the trimmed value.
+//!
+//! The unit of length is selected by the type used: [`Bytes`], [`Chars`], or (behind the
+//! `graphemes` crate feature) [`Graphemes`]. Both bounds are inclusive, and a minimum greater than
+//! the maximum fails to compile:
+//!
+//! ```compile_fail
+//! #[derive(Debug, serde::Deserialize)]
+//! struct Form {
+//!     #[serde(deserialize_with = "detrim::bounded::Chars::<8, 1>::string")]
+//!     name: String,
+//! }
+//!
+//! serde_json::from_str::<Form>(r#"{ "name": "ferris" }"#).unwrap();
+//! ```
+//!
+//! # Examples
+//!
+//! ```
+//! #[derive(Debug, serde::Deserialize)]
+//! struct Form {
+//!     #[serde(deserialize_with = "detrim::bounded::Chars::<1, 8>::string")]
+//!     name: String,
+//! }
+//!
+//! let form = serde_json::from_str::<Form>(r#"{ "name": "  ferris   " }"#).unwrap();
+//! assert_eq!(form.name, "ferris");
+//!
+//! serde_json::from_str::<Form>(r#"{ "name": "   " }"#).unwrap_err();
+//! serde_json::from_str::<Form>(r#"{ "name": "ferris the crab" }"#).unwrap_err();
+//! ```
+
+use alloc::{string::String, vec::Vec};
+use core::marker::PhantomData;
+
+use serde::{de, Deserialize as _, Deserializer};
+
+use crate::{error::Error, seq::VecSeedVisitor, string::trim_in_place};
+
+/// Length bounds measured in bytes.
+///
+/// Truncation happens on a `char` boundary. This can separate combining marks from the character
+/// they modify, like the accent in `"e\u{301}"`, unless the `graphemes` crate feature is enabled,
+/// in which case truncation moves back to the previous grapheme cluster boundary.
+#[derive(Debug)]
+pub enum Bytes<const MIN: usize, const MAX: usize> {}
+
+/// Length bounds measured in `char`s.
+///
+/// Truncation happens on a `char` boundary, with the same caveat as for [`Bytes`].
+#[derive(Debug)]
+pub enum Chars<const MIN: usize, const MAX: usize> {}
+
+/// Length bounds measured in extended grapheme clusters.
+///
+/// Truncation happens on a grapheme cluster boundary.
+#[cfg(feature = "graphemes")]
+#[derive(Debug)]
+pub enum Graphemes<const MIN: usize, const MAX: usize> {}
+
+/// Unit in which string length is measured.
+trait Unit {
+    /// Plural name of unit used in error messages.
+    const NAME: &'static str;
+
+    /// Returns length of `val` in this unit.
+    fn len(val: &str) -> usize;
+
+    /// Returns byte index at which to cut `val` so it is at most `max` units long.
+    fn truncate_at(val: &str, max: usize) -> usize;
+}
+
+impl<const MIN: usize, const MAX: usize> Unit for Bytes<MIN, MAX> {
+    const NAME: &'static str = "bytes";
+
+    fn len(val: &str) -> usize {
+        val.len()
+    }
+
+    fn truncate_at(val: &str, max: usize) -> usize {
+        if max >= val.len() {
+            return val.len();
+        }
+
+        let idx = (0..=max)
+            .rev()
+            .find(|&idx| val.is_char_boundary(idx))
+            .unwrap_or(0);
+
+        grapheme_boundary_before(val, idx)
+    }
+}
+
+impl<const MIN: usize, const MAX: usize> Unit for Chars<MIN, MAX> {
+    const NAME: &'static str = "chars";
+
+    fn len(val: &str) -> usize {
+        val.chars().count()
+    }
+
+    fn truncate_at(val: &str, max: usize) -> usize {
+        let idx = val
+            .char_indices()
+            .nth(max)
+            .map_or(val.len(), |(idx, _)| idx);
+
+        grapheme_boundary_before(val, idx)
+    }
+}
+
+#[cfg(feature = "graphemes")]
+impl<const MIN: usize, const MAX: usize> Unit for Graphemes<MIN, MAX> {
+    const NAME: &'static str = "grapheme clusters";
+
+    fn len(val: &str) -> usize {
+        unicode_segmentation::UnicodeSegmentation::graphemes(val, true).count()
+    }
+
+    fn truncate_at(val: &str, max: usize) -> usize {
+        unicode_segmentation::UnicodeSegmentation::grapheme_indices(val, true)
+            .nth(max)
+            .map_or(val.len(), |(idx, _)| idx)
+    }
+}
+
+/// Returns the start of the grapheme cluster containing byte index `idx` of `val`.
+#[cfg(feature = "graphemes")]
+fn grapheme_boundary_before(val: &str, idx: usize) -> usize {
+    if idx == val.len() {
+        return idx;
+    }
+
+    unicode_segmentation::UnicodeSegmentation::grapheme_indices(val, true)
+        .map(|(start, _)| start)
+        .take_while(|&start| start <= idx)
+        .last()
+        .unwrap_or(0)
+}
+
+/// Returns `idx` unchanged, since grapheme cluster boundaries are unknown without the `graphemes`
+/// crate feature.
+#[cfg(not(feature = "graphemes"))]
+fn grapheme_boundary_before(_val: &str, idx: usize) -> usize {
+    idx
+}
+
+/// Trims `val`, returning error if its trimmed length is out of bounds.
+///
+/// If `truncate` is set, values that are too long are truncated to `max` instead. The length of
+/// the truncated value is still checked against `min`, since truncation can remove more than
+/// needed to keep grapheme clusters intact.
+fn check<U: Unit, E: de::Error>(
+    mut val: String,
+    min: usize,
+    max: usize,
+    truncate: bool,
+) -> Result<String, E> {
+    let trimmed = val.trim();
+    let mut len = U::len(trimmed);
+    let mut end = trimmed.len();
+
+    if truncate && len > max {
+        end = U::truncate_at(trimmed, max);
+        len = U::len(&trimmed[..end]);
+    }
+
+    if len < min || len > max {
+        return Err(Error::Length {
+            original: val,
+            len,
+            min,
+            max,
+            unit: U::NAME,
+        }
+        .into_de_error());
+    }
+
+    trim_in_place(&mut val);
+    val.truncate(end);
+
+    Ok(val)
+}
+
+/// Seed that deserializes a sequence item, checking its length inside its own deserialization so
+/// that errors carry the item's path.
+struct CheckSeed<U> {
+    min: usize,
+    max: usize,
+    unit: PhantomData<U>,
+}
+
+impl<'de, U: Unit> de::DeserializeSeed<'de> for CheckSeed<U> {
+    type Value = String;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        let mut val = check::<U, _>(String::deserialize(de)?, self.min, self.max, false)?;
+        val.shrink_to_fit();
+        Ok(val)
+    }
+}
+
+macro_rules! impl_bounded {
+    ($ty:ident) => {
+        impl<const MIN: usize, const MAX: usize> $ty<MIN, MAX> {
+            /// Fails to compile when evaluated, if the bounds are out of order.
+            const ORDERED: () = assert!(MIN <= MAX, "minimum length exceeds maximum length");
+
+            /// Trims a string during deserialization, returning error if its length is out of
+            /// bounds.
+            pub fn string<'de, D: Deserializer<'de>>(de: D) -> Result<String, D::Error> {
+                let () = Self::ORDERED;
+                check::<Self, _>(String::deserialize(de)?, MIN, MAX, false)
+            }
+
+            /// Trims an optional string during deserialization, returning error if its length is
+            /// out of bounds.
+            pub fn option_string<'de, D: Deserializer<'de>>(
+                de: D,
+            ) -> Result<Option<String>, D::Error> {
+                let () = Self::ORDERED;
+                Option::<String>::deserialize(de)?
+                    .map(|val| check::<Self, _>(val, MIN, MAX, false))
+                    .transpose()
+            }
+
+            /// Trims list of strings during deserialization, returning error if the length of
+            /// any item is out of bounds.
+            pub fn vec_string<'de, D: Deserializer<'de>>(de: D) -> Result<Vec<String>, D::Error> {
+                let () = Self::ORDERED;
+                de.deserialize_seq(VecSeedVisitor::new("a sequence of strings", |_| {
+                    CheckSeed {
+                        min: MIN,
+                        max: MAX,
+                        unit: PhantomData::<Self>,
+                    }
+                }))
+            }
+
+            /// Trims a string during deserialization, truncating it to the maximum length and
+            /// returning error if it is too short.
+            pub fn string_truncate<'de, D: Deserializer<'de>>(de: D) -> Result<String, D::Error> {
+                let () = Self::ORDERED;
+                check::<Self, _>(String::deserialize(de)?, MIN, MAX, true)
+            }
+        }
+    };
+}
+
+impl_bounded!(Bytes);
+impl_bounded!(Chars);
+#[cfg(feature = "graphemes")]
+impl_bounded!(Graphemes);
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString as _;
+
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[test]
+    fn bytes() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::Bytes::<1, 4>::string")]
+            foo: String,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": 1 }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": "" }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": "   " }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": "barbaz" }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": "ñññ" }"#).unwrap_err();
+
+        let err = serde_json::from_str::<Foo>(r#"{ "foo": " barbaz " }"#).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("invalid length 6, expected a trimmed string of 1 to 4 bytes"));
+        assert!(err.to_string().contains(r#"(original value: " barbaz ")"#));
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": "  bar  " }"#)
+                .unwrap()
+                .foo,
+            "bar",
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": " ññ " }"#)
+                .unwrap()
+                .foo,
+            "ññ",
+        );
+    }
+
+    #[test]
+    fn chars() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::Chars::<2, 3>::string")]
+            foo: String,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": " b " }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": "barb" }"#).unwrap_err();
+
+        let err = serde_json::from_str::<Foo>(r#"{ "foo": "    b" }"#).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("invalid length 1, expected a trimmed string of 2 to 3 chars"));
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": "  ba  " }"#)
+                .unwrap()
+                .foo,
+            "ba",
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": " ñññ " }"#)
+                .unwrap()
+                .foo,
+            "ñññ",
+        );
+    }
+
+    #[test]
+    fn option_string() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::Chars::<1, 3>::option_string")]
+            foo: Option<String>,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": " " }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": "barb" }"#).unwrap_err();
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": null }"#)
+                .unwrap()
+                .foo,
+            None,
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": " bar " }"#)
+                .unwrap()
+                .foo
+                .as_deref(),
+            Some("bar"),
+        );
+    }
+
+    #[test]
+    fn vec_string() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::Chars::<1, 3>::vec_string")]
+            foo: Vec<String>,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": ["bar", " "] }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": ["barb"] }"#).unwrap_err();
+
+        assert!(serde_json::from_str::<Foo>(r#"{ "foo": [] }"#)
+            .unwrap()
+            .foo
+            .is_empty());
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": [" bar ", "b "] }"#)
+                .unwrap()
+                .foo,
+            ["bar", "b"],
+        );
+    }
+
+    #[test]
+    fn string_truncate() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::Bytes::<1, 3>::string_truncate")]
+            foo: String,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Bar {
+            #[serde(deserialize_with = "super::Chars::<1, 3>::string_truncate")]
+            bar: String,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": "  " }"#).unwrap_err();
+        serde_json::from_str::<Bar>(r#"{ "bar": "  " }"#).unwrap_err();
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": " barbaz " }"#)
+                .unwrap()
+                .foo,
+            "bar",
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": "ññ" }"#)
+                .unwrap()
+                .foo,
+            "ñ",
+        );
+        assert_eq!(
+            serde_json::from_str::<Bar>(r#"{ "bar": " ññññ " }"#)
+                .unwrap()
+                .bar,
+            "ñññ",
+        );
+    }
+
+    #[cfg(feature = "graphemes")]
+    #[test]
+    fn graphemes() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::Graphemes::<1, 2>::string")]
+            foo: String,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Bar {
+            #[serde(deserialize_with = "super::Graphemes::<1, 2>::string_truncate")]
+            bar: String,
+        }
+
+        // "e" followed by a combining acute accent is two chars but one grapheme cluster
+        serde_json::from_str::<Foo>(r#"{ "foo": "e\u0301e\u0301e\u0301" }"#).unwrap_err();
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": " e\u0301e\u0301 " }"#)
+                .unwrap()
+                .foo,
+            "e\u{301}e\u{301}",
+        );
+        assert_eq!(
+            serde_json::from_str::<Bar>(r#"{ "bar": " e\u0301e\u0301e\u0301 " }"#)
+                .unwrap()
+                .bar,
+            "e\u{301}e\u{301}",
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "graphemes")]
+    fn truncate_combining_marks() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::Bytes::<1, 2>::string_truncate")]
+            foo: String,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Bar {
+            #[serde(deserialize_with = "super::Chars::<2, 2>::string_truncate")]
+            bar: String,
+        }
+
+        // cutting after 2 bytes or chars would separate the accent from its "e"
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": "ae\u0301" }"#)
+                .unwrap()
+                .foo,
+            "a",
+        );
+        assert_eq!(
+            serde_json::from_str::<Bar>(r#"{ "bar": "e\u0301e\u0301" }"#)
+                .unwrap()
+                .bar,
+            "e\u{301}",
+        );
+
+        // keeping the accent attached leaves fewer chars than the minimum
+        serde_json::from_str::<Bar>(r#"{ "bar": "ae\u0301" }"#).unwrap_err();
+    }
+}
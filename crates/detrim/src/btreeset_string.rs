@@ -0,0 +1,114 @@
+use alloc::{collections::BTreeSet, string::String};
+
+use serde::Deserializer;
+
+use crate::seq::{EmptyItems, TrimSeqVisitor};
+
+/// Trims set of strings during deserialization.
+///
+/// Strings are deduplicated _after_ being trimmed (i.e., differences in extraneous whitespace are
+/// handled). Unlike `hashset_string()`, this does not require the `std` crate feature.
+pub fn btreeset_string<'a, D: Deserializer<'a>>(de: D) -> Result<BTreeSet<String>, D::Error> {
+    de.deserialize_seq(TrimSeqVisitor::new(EmptyItems::Keep, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString as _;
+
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq, Eq)]
+    struct Foo {
+        #[serde(deserialize_with = "super::btreeset_string")]
+        foo: BTreeSet<String>,
+    }
+
+    impl Foo {
+        fn new(foo: impl IntoIterator<Item = impl Into<String>>) -> Self {
+            Self {
+                foo: foo.into_iter().map(Into::into).collect(),
+            }
+        }
+    }
+
+    #[test]
+    fn btreeset_string() {
+        serde_json::from_str::<Foo>(r#"{ "foo": 1 }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": "" }"#).unwrap_err();
+
+        assert_eq!(
+            Foo::new([""; 0]),
+            serde_json::from_str(r#"{ "foo": [] }"#).unwrap(),
+        );
+        assert_eq!(
+            Foo::new([""]),
+            serde_json::from_str(r#"{ "foo": ["", " "] }"#).unwrap(),
+        );
+        assert_eq!(
+            Foo::new(["bar"]),
+            serde_json::from_str(r#"{ "foo": ["  bar  ", "  bar", "bar "] }"#).unwrap(),
+        );
+        assert_eq!(
+            Foo::new(["bar", "baz"]),
+            serde_json::from_str(r#"{ "foo": [" baz", "bar "] }"#).unwrap(),
+        );
+    }
+
+    #[test]
+    fn btreeset_string_non_empty() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Bar {
+            #[serde(deserialize_with = "crate::seq::NonEmpty::<false>::btreeset_string")]
+            foo: BTreeSet<String>,
+        }
+
+        serde_json::from_str::<Bar>(r#"{ "foo": [""] }"#).unwrap_err();
+
+        let err = serde_json::from_str::<Bar>(r#"{ "foo": ["bar", "bar", " "] }"#).unwrap_err();
+        assert!(err.to_string().contains("empty string at index 2"));
+
+        let bar = serde_json::from_str::<Bar>(r#"{ "foo": ["  bar  ", "bar"] }"#).unwrap();
+        assert_eq!(bar.foo, Foo::new(["bar"]).foo);
+    }
+
+    #[test]
+    fn btreeset_string_skip_empty() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Bar {
+            #[serde(deserialize_with = "crate::seq::SkipEmpty::<false>::btreeset_string")]
+            foo: BTreeSet<String>,
+        }
+
+        let bar = serde_json::from_str::<Bar>(r#"{ "foo": ["", " "] }"#).unwrap();
+        assert!(bar.foo.is_empty());
+
+        let bar = serde_json::from_str::<Bar>(r#"{ "foo": [" bar", "  ", "bar "] }"#).unwrap();
+        assert_eq!(bar.foo, Foo::new(["bar"]).foo);
+    }
+
+    #[test]
+    fn btreeset_string_at_least_one() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Bar {
+            #[serde(deserialize_with = "crate::seq::NonEmpty::<true>::btreeset_string")]
+            foo: BTreeSet<String>,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Baz {
+            #[serde(deserialize_with = "crate::seq::SkipEmpty::<true>::btreeset_string")]
+            baz: BTreeSet<String>,
+        }
+
+        serde_json::from_str::<Bar>(r#"{ "foo": [] }"#).unwrap_err();
+        serde_json::from_str::<Bar>(r#"{ "foo": [" "] }"#).unwrap_err();
+        serde_json::from_str::<Bar>(r#"{ "foo": [" bar "] }"#).unwrap();
+
+        serde_json::from_str::<Baz>(r#"{ "baz": [] }"#).unwrap_err();
+        serde_json::from_str::<Baz>(r#"{ "baz": [" ", ""] }"#).unwrap_err();
+        serde_json::from_str::<Baz>(r#"{ "baz": [" ", " baz "] }"#).unwrap();
+    }
+}
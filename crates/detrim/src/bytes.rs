@@ -0,0 +1,367 @@
+use alloc::{string::String, vec::Vec};
+use core::{fmt, marker::PhantomData};
+
+use serde::{de, Deserializer};
+
+use crate::seq::VecSeedVisitor;
+
+/// Trims ASCII whitespace from both ends of a byte string.
+///
+/// Removed whitespace is reported to the active observer.
+fn trim_ascii(val: &[u8]) -> &[u8] {
+    let start = val
+        .iter()
+        .position(|byte| !byte.is_ascii_whitespace())
+        .unwrap_or(val.len());
+    let end = val
+        .iter()
+        .rposition(|byte| !byte.is_ascii_whitespace())
+        .map_or(start, |idx| idx + 1);
+
+    #[cfg(feature = "observe")]
+    crate::observe::report(start, val.len() - end);
+
+    &val[start..end]
+}
+
+/// Trims ASCII whitespace from both ends of a byte string in place, without re-allocating.
+fn trim_ascii_in_place(val: &mut Vec<u8>) {
+    let trimmed = trim_ascii(val);
+    let start = trimmed.as_ptr() as usize - val.as_ptr() as usize;
+    let end = start + trimmed.len();
+
+    val.truncate(end);
+    val.drain(..start);
+}
+
+/// Visitor that borrows a trimmed byte string from the input.
+struct BytesVisitor;
+
+impl<'a> de::Visitor<'a> for BytesVisitor {
+    type Value = &'a [u8];
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a borrowed byte string")
+    }
+
+    fn visit_borrowed_bytes<E: de::Error>(self, val: &'a [u8]) -> Result<Self::Value, E> {
+        Ok(trim_ascii(val))
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, val: &'a str) -> Result<Self::Value, E> {
+        Ok(trim_ascii(val.as_bytes()))
+    }
+}
+
+/// Trims ASCII whitespace from a borrowed byte string during deserialization.
+///
+/// Bytes are not required to be valid UTF-8. Strings are also accepted, so long as they can be
+/// borrowed from the input.
+pub fn bytes<'a, 'de: 'a, D: Deserializer<'de>>(de: D) -> Result<&'a [u8], D::Error> {
+    de.deserialize_bytes(BytesVisitor)
+}
+
+/// Trims ASCII whitespace from an optional borrowed byte string during deserialization.
+///
+/// See [`bytes()`] for details.
+pub fn option_bytes<'a, 'de: 'a, D: Deserializer<'de>>(
+    de: D,
+) -> Result<Option<&'a [u8]>, D::Error> {
+    struct OptionBytesVisitor;
+
+    impl<'a> de::Visitor<'a> for OptionBytesVisitor {
+        type Value = Option<&'a [u8]>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("an optional borrowed byte string")
+        }
+
+        fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D: Deserializer<'a>>(self, de: D) -> Result<Self::Value, D::Error> {
+            bytes(de).map(Some)
+        }
+    }
+
+    de.deserialize_option(OptionBytesVisitor)
+}
+
+/// Visitor and seed that constructs `T` from a trimmed, owned byte string.
+struct TrimBytesInto<T>(PhantomData<fn() -> T>);
+
+impl<T> TrimBytesInto<T> {
+    fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<'de, T: From<Vec<u8>>> de::Visitor<'de> for TrimBytesInto<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a byte string")
+    }
+
+    fn visit_bytes<E: de::Error>(self, val: &[u8]) -> Result<Self::Value, E> {
+        Ok(T::from(trim_ascii(val).to_vec()))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, mut val: Vec<u8>) -> Result<Self::Value, E> {
+        trim_ascii_in_place(&mut val);
+        Ok(T::from(val))
+    }
+
+    fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+        self.visit_bytes(val.as_bytes())
+    }
+
+    fn visit_string<E: de::Error>(self, val: String) -> Result<Self::Value, E> {
+        self.visit_byte_buf(val.into_bytes())
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        let bytes = VecSeedVisitor::new("a sequence of bytes", |_| PhantomData::<u8>);
+        self.visit_byte_buf(de::Visitor::visit_seq(bytes, seq)?)
+    }
+}
+
+impl<'de, T: From<Vec<u8>>> de::DeserializeSeed<'de> for TrimBytesInto<T> {
+    type Value = T;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_byte_buf(self)
+    }
+}
+
+struct OptionTrimBytesInto<T>(PhantomData<fn() -> T>);
+
+impl<'de, T: From<Vec<u8>>> de::Visitor<'de> for OptionTrimBytesInto<T> {
+    type Value = Option<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("an optional byte string")
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_byte_buf(TrimBytesInto::new()).map(Some)
+    }
+}
+
+macro_rules! impl_trim_bytes_into {
+    ($ty:ty, $name:literal, $single:ident, $option:ident, $vec:ident) => {
+        #[doc = concat!(
+            "Trims ASCII whitespace from a byte string into ", $name, " during deserialization."
+        )]
+        ///
+        /// Bytes are not required to be valid UTF-8. Strings and sequences of bytes are also
+        /// accepted.
+        pub fn $single<'de, D: Deserializer<'de>>(de: D) -> Result<$ty, D::Error> {
+            de.deserialize_byte_buf(TrimBytesInto::new())
+        }
+
+        #[doc = concat!(
+            "Trims ASCII whitespace from an optional byte string into ",
+            $name,
+            " during deserialization."
+        )]
+        ///
+        #[doc = concat!("See [`", stringify!($single), "()`] for details.")]
+        pub fn $option<'de, D: Deserializer<'de>>(de: D) -> Result<Option<$ty>, D::Error> {
+            de.deserialize_option(OptionTrimBytesInto(PhantomData))
+        }
+
+        #[doc = concat!(
+            "Trims ASCII whitespace from list of byte strings into ",
+            $name,
+            "s during deserialization."
+        )]
+        ///
+        #[doc = concat!("See [`", stringify!($single), "()`] for details.")]
+        pub fn $vec<'de, D: Deserializer<'de>>(de: D) -> Result<Vec<$ty>, D::Error> {
+            de.deserialize_seq(VecSeedVisitor::new("a sequence of byte strings", |_| {
+                TrimBytesInto::new()
+            }))
+        }
+    };
+}
+
+impl_trim_bytes_into!(
+    Vec<u8>,
+    "a `Vec<u8>`",
+    byte_buf,
+    option_byte_buf,
+    vec_byte_buf
+);
+
+#[cfg(feature = "bytes")]
+impl_trim_bytes_into!(
+    bytes::Bytes,
+    "a `Bytes`",
+    shared_bytes,
+    option_shared_bytes,
+    vec_shared_bytes
+);
+
+#[cfg(test)]
+mod tests {
+    use serde::{
+        de::value::{BorrowedBytesDeserializer, BytesDeserializer, Error},
+        Deserialize,
+    };
+
+    use super::*;
+
+    #[test]
+    fn trim() {
+        assert_eq!(trim_ascii(b""), b"");
+        assert_eq!(trim_ascii(b" \t\r\n\x0C"), b"");
+        assert_eq!(trim_ascii(b" a b "), b"a b");
+        assert_eq!(trim_ascii(b"\xe9t\xe9\n"), b"\xe9t\xe9");
+
+        // vertical tab is not ASCII whitespace
+        assert_eq!(trim_ascii(b"\x0Ba "), b"\x0Ba");
+
+        let mut val = b"\t foo \r\n".to_vec();
+        trim_ascii_in_place(&mut val);
+        assert_eq!(val, b"foo");
+
+        let mut val = b"  ".to_vec();
+        trim_ascii_in_place(&mut val);
+        assert!(val.is_empty());
+    }
+
+    #[test]
+    fn bytes() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo<'a> {
+            #[serde(borrow, deserialize_with = "super::bytes")]
+            foo: &'a [u8],
+        }
+
+        serde_json::from_slice::<Foo<'_>>(br#"{ "foo": 1 }"#).unwrap_err();
+
+        let json = serde_json::from_slice::<Foo<'_>>(br#"{ "foo": " bar " }"#).unwrap();
+        assert_eq!(json.foo, b"bar");
+
+        let de = BorrowedBytesDeserializer::<Error>::new(b" \xff\xfe\n");
+        assert_eq!(super::bytes(de).unwrap(), b"\xff\xfe");
+
+        // can not borrow
+        super::bytes(BytesDeserializer::<Error>::new(b"foo")).unwrap_err();
+    }
+
+    #[test]
+    fn option_bytes() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo<'a> {
+            #[serde(borrow, deserialize_with = "super::option_bytes")]
+            foo: Option<&'a [u8]>,
+        }
+
+        let json = serde_json::from_slice::<Foo<'_>>(br#"{ "foo": null }"#).unwrap();
+        assert_eq!(json.foo, None);
+
+        let json = serde_json::from_slice::<Foo<'_>>(br#"{ "foo": " bar" }"#).unwrap();
+        assert_eq!(json.foo, Some(&b"bar"[..]));
+    }
+
+    #[test]
+    fn byte_buf() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::byte_buf")]
+            foo: Vec<u8>,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": 1 }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": [256] }"#).unwrap_err();
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": " b\\ar " }"#)
+                .unwrap()
+                .foo,
+            b"b\\ar",
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": [32, 233, 9] }"#)
+                .unwrap()
+                .foo,
+            b"\xe9",
+        );
+
+        let de = BytesDeserializer::<Error>::new(b"\r\n\xe9t\xe9 ");
+        assert_eq!(super::byte_buf(de).unwrap(), b"\xe9t\xe9");
+    }
+
+    #[test]
+    fn option_byte_buf() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::option_byte_buf")]
+            foo: Option<Vec<u8>>,
+        }
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": null }"#)
+                .unwrap()
+                .foo,
+            None,
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": " bar " }"#)
+                .unwrap()
+                .foo
+                .as_deref(),
+            Some(&b"bar"[..]),
+        );
+    }
+
+    #[test]
+    fn vec_byte_buf() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::vec_byte_buf")]
+            foo: Vec<Vec<u8>>,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": "bar" }"#).unwrap_err();
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": [" bar", [98, 32]] }"#)
+                .unwrap()
+                .foo,
+            [&b"bar"[..], b"b"],
+        );
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn shared_bytes() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::shared_bytes")]
+            foo: bytes::Bytes,
+        }
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": " bar " }"#)
+                .unwrap()
+                .foo,
+            "bar",
+        );
+    }
+}
@@ -0,0 +1,366 @@
+//! Trimming deserializers that also accept scalars, converting them to strings.
+//!
+//! Formats like YAML and TOML infer types for unquoted values, so a field intended to be a string
+//! may arrive as a number or boolean. The functions in this module accept booleans, integers,
+//! floats, and chars as well as strings, formatting them with their [`Display`](fmt::Display)
+//! implementation. Strings are trimmed the same way as by [`crate::string()`].
+//!
+//! Formatting floats is lossy: `1.10` becomes `"1.1"` and `1.0` becomes `"1"`. Use the functions on
+//! [`RejectFloats`] to return an error for floats instead.
+//!
+//! Leading zeros can't be recovered either, since the format has already parsed the value as an
+//! integer: `zip: 01234` becomes `"1234"` and `code: 007` becomes `"7"`. YAML 1.1 parsers read
+//! `01234` as octal, so it becomes `"668"` there. Fields that can have leading zeros, like postal
+//! codes or identifiers, must be quoted in the input.
+//!
+//! Since the input type is detected at runtime, this is only supported by self-describing formats.
+//! If serde_json's `arbitrary_precision` feature is enabled, enable the `arbitrary_precision` crate
+//! feature too, so that numbers keep the digits they were written with, like `1.10`.
+//!
+//! # Examples
+//!
+//! ```
+//! #[derive(Debug, serde::Deserialize)]
+//! struct Config {
+//!     #[serde(deserialize_with = "detrim::coerce::string")]
+//!     version: String,
+//!     #[serde(deserialize_with = "detrim::coerce::RejectFloats::string")]
+//!     code: String,
+//! }
+//!
+//! let config = serde_json::from_str::<Config>(r#"{ "version": 2, "code": " 007 " }"#).unwrap();
+//! assert_eq!(config.version, "2");
+//! assert_eq!(config.code, "007");
+//!
+//! serde_json::from_str::<Config>(r#"{ "version": 2, "code": 1.5 }"#).unwrap_err();
+//! ```
+
+use alloc::{
+    string::{String, ToString as _},
+    vec::Vec,
+};
+use core::{fmt, str};
+
+#[cfg(feature = "arbitrary_precision")]
+use serde::Deserialize as _;
+use serde::{de, Deserializer};
+
+use crate::{
+    seq::VecSeedVisitor,
+    string::{trim, trim_in_place},
+};
+
+/// Coercing deserializers that return an error for floats.
+///
+/// See [module docs](self) for more.
+#[derive(Debug)]
+pub enum RejectFloats {}
+
+impl RejectFloats {
+    /// Trims a string, or formats a non-float scalar, during deserialization.
+    pub fn string<'de, D: Deserializer<'de>>(de: D) -> Result<String, D::Error> {
+        de.deserialize_any(CoerceVisitor { floats: false })
+    }
+
+    /// Trims an optional string, or formats a non-float scalar, during deserialization.
+    pub fn option_string<'de, D: Deserializer<'de>>(de: D) -> Result<Option<String>, D::Error> {
+        de.deserialize_option(OptionCoerceVisitor { floats: false })
+    }
+
+    /// Trims list of strings, or formats non-float scalars, during deserialization.
+    pub fn vec_string<'de, D: Deserializer<'de>>(de: D) -> Result<Vec<String>, D::Error> {
+        de.deserialize_seq(vec_coerce_visitor(false))
+    }
+}
+
+/// Trims a string, or formats a scalar, during deserialization.
+pub fn string<'de, D: Deserializer<'de>>(de: D) -> Result<String, D::Error> {
+    de.deserialize_any(CoerceVisitor { floats: true })
+}
+
+/// Trims an optional string, or formats a scalar, during deserialization.
+pub fn option_string<'de, D: Deserializer<'de>>(de: D) -> Result<Option<String>, D::Error> {
+    de.deserialize_option(OptionCoerceVisitor { floats: true })
+}
+
+/// Trims list of strings, or formats scalars, during deserialization.
+pub fn vec_string<'de, D: Deserializer<'de>>(de: D) -> Result<Vec<String>, D::Error> {
+    de.deserialize_seq(vec_coerce_visitor(true))
+}
+
+/// Visitor and seed that trims a string or formats a scalar.
+#[derive(Clone, Copy)]
+struct CoerceVisitor {
+    floats: bool,
+}
+
+impl CoerceVisitor {
+    fn float<E: de::Error>(self, val: f64, formatted: String) -> Result<String, E> {
+        if !self.floats {
+            return Err(de::Error::invalid_type(de::Unexpected::Float(val), &self));
+        }
+
+        Ok(formatted)
+    }
+}
+
+macro_rules! visit_display {
+    ($($method:ident($ty:ty)),* $(,)?) => {
+        $(
+            fn $method<E: de::Error>(self, val: $ty) -> Result<Self::Value, E> {
+                Ok(val.to_string())
+            }
+        )*
+    };
+}
+
+impl<'de> de::Visitor<'de> for CoerceVisitor {
+    type Value = String;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.floats {
+            formatter.write_str("a string, boolean, integer, float, or char")
+        } else {
+            formatter.write_str("a string, boolean, integer, or char")
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+        Ok(trim(val).to_string())
+    }
+
+    fn visit_string<E: de::Error>(self, mut val: String) -> Result<Self::Value, E> {
+        trim_in_place(&mut val);
+        Ok(val)
+    }
+
+    fn visit_bytes<E: de::Error>(self, val: &[u8]) -> Result<Self::Value, E> {
+        match str::from_utf8(val) {
+            Ok(val) => self.visit_str(val),
+            Err(_) => Err(de::Error::invalid_value(de::Unexpected::Bytes(val), &self)),
+        }
+    }
+
+    visit_display! {
+        visit_bool(bool),
+        visit_i64(i64),
+        visit_i128(i128),
+        visit_u64(u64),
+        visit_u128(u128),
+        visit_char(char),
+    }
+
+    // formatted directly, since widening to `f64` would show the `f32` rounding error
+    fn visit_f32<E: de::Error>(self, val: f32) -> Result<Self::Value, E> {
+        self.float(val.into(), val.to_string())
+    }
+
+    fn visit_f64<E: de::Error>(self, val: f64) -> Result<Self::Value, E> {
+        self.float(val, val.to_string())
+    }
+
+    #[cfg(feature = "arbitrary_precision")]
+    fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        // serde_json's `arbitrary_precision` feature presents numbers as a map
+        let number = serde_json::Number::deserialize(de::value::MapAccessDeserializer::new(map))?;
+        let val = number.to_string();
+
+        if !self.floats && val.contains(['.', 'e', 'E']) {
+            return Err(de::Error::invalid_type(de::Unexpected::Other(&val), &self));
+        }
+
+        Ok(val)
+    }
+}
+
+impl<'de> de::DeserializeSeed<'de> for CoerceVisitor {
+    type Value = String;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_any(self)
+    }
+}
+
+struct OptionCoerceVisitor {
+    floats: bool,
+}
+
+impl<'de> de::Visitor<'de> for OptionCoerceVisitor {
+    type Value = Option<String>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("an optional string or scalar")
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_any(CoerceVisitor {
+            floats: self.floats,
+        })
+        .map(Some)
+    }
+}
+
+/// Returns a visitor that coerces each item of a sequence.
+fn vec_coerce_visitor(floats: bool) -> VecSeedVisitor<impl FnMut(usize) -> CoerceVisitor> {
+    VecSeedVisitor::new("a sequence of strings or scalars", move |_| CoerceVisitor {
+        floats,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{
+        de::{value::Error, IntoDeserializer as _},
+        Deserialize,
+    };
+
+    use super::*;
+
+    #[test]
+    fn string() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::string")]
+            foo: String,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": null }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": [] }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": {} }"#).unwrap_err();
+
+        for (json, expected) in [
+            (r#"{ "foo": " bar " }"#, "bar"),
+            (r#"{ "foo": " b\\ar " }"#, "b\\ar"),
+            (r#"{ "foo": true }"#, "true"),
+            (r#"{ "foo": -12 }"#, "-12"),
+            (r#"{ "foo": 18446744073709551615 }"#, "18446744073709551615"),
+            (r#"{ "foo": 1.5 }"#, "1.5"),
+        ] {
+            assert_eq!(serde_json::from_str::<Foo>(json).unwrap().foo, expected);
+        }
+
+        let val = super::string('x'.into_deserializer()).map_err(|err: Error| err);
+        assert_eq!(val.unwrap(), "x");
+
+        let val = super::string(1.1_f32.into_deserializer()).map_err(|err: Error| err);
+        assert_eq!(val.unwrap(), "1.1");
+    }
+
+    #[cfg(feature = "arbitrary_precision")]
+    #[test]
+    fn arbitrary_precision() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::string")]
+            foo: String,
+            #[serde(default, deserialize_with = "super::RejectFloats::string")]
+            bar: String,
+        }
+
+        // numbers keep the form they were written in
+        for (json, expected) in [
+            (r#"{ "foo": 1.10 }"#, "1.10"),
+            (
+                r#"{ "foo": 340282366920938463463374607431768211456 }"#,
+                "340282366920938463463374607431768211456",
+            ),
+        ] {
+            assert_eq!(serde_json::from_str::<Foo>(json).unwrap().foo, expected);
+        }
+
+        let foo = serde_json::from_str::<Foo>(r#"{ "foo": "", "bar": 12 }"#).unwrap();
+        assert_eq!(foo.bar, "12");
+
+        serde_json::from_str::<Foo>(r#"{ "foo": "", "bar": 1.0 }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": "", "bar": 1E3 }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": { "a": "1" } }"#).unwrap_err();
+    }
+
+    #[test]
+    fn reject_floats() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "RejectFloats::string")]
+            foo: String,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": 1.5 }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": 1e3 }"#).unwrap_err();
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": 15 }"#).unwrap().foo,
+            "15",
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": " 1.5 " }"#)
+                .unwrap()
+                .foo,
+            "1.5",
+        );
+
+        super::RejectFloats::string(1.5_f32.into_deserializer())
+            .map_err(|err: Error| err)
+            .unwrap_err();
+    }
+
+    #[test]
+    fn leading_zeros() {
+        // formats that accept an unquoted `007` hand over the parsed integer
+        let parsed = "007".parse::<u64>().unwrap();
+
+        let val = super::string(parsed.into_deserializer()).map_err(|err: Error| err);
+        assert_eq!(val.unwrap(), "7");
+
+        let val = super::RejectFloats::string(parsed.into_deserializer()).map_err(|err: Error| err);
+        assert_eq!(val.unwrap(), "7");
+    }
+
+    #[test]
+    fn option_string() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::option_string")]
+            foo: Option<String>,
+            #[serde(deserialize_with = "RejectFloats::option_string")]
+            bar: Option<String>,
+        }
+
+        let foo = serde_json::from_str::<Foo>(r#"{ "foo": null, "bar": 1 }"#).unwrap();
+        assert_eq!(foo.foo, None);
+        assert_eq!(foo.bar.as_deref(), Some("1"));
+
+        let foo = serde_json::from_str::<Foo>(r#"{ "foo": 0.5, "bar": " x " }"#).unwrap();
+        assert_eq!(foo.foo.as_deref(), Some("0.5"));
+        assert_eq!(foo.bar.as_deref(), Some("x"));
+
+        serde_json::from_str::<Foo>(r#"{ "foo": null, "bar": 0.5 }"#).unwrap_err();
+    }
+
+    #[test]
+    fn vec_string() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::vec_string")]
+            foo: Vec<String>,
+            #[serde(deserialize_with = "RejectFloats::vec_string")]
+            bar: Vec<String>,
+        }
+
+        let foo =
+            serde_json::from_str::<Foo>(r#"{ "foo": [" a ", 1, 2.5, false], "bar": [1, "b "] }"#)
+                .unwrap();
+        assert_eq!(foo.foo, ["a", "1", "2.5", "false"]);
+        assert_eq!(foo.bar, ["1", "b"]);
+
+        serde_json::from_str::<Foo>(r#"{ "foo": "a", "bar": [] }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": [], "bar": [1, 2.5] }"#).unwrap_err();
+    }
+}
@@ -8,55 +8,134 @@ use core::fmt;
 
 use serde::{de, Deserializer};
 
-/// Trims a CoW string during deserialization.
-pub fn cow_str<'a, 'de: 'a, D: Deserializer<'de>>(de: D) -> Result<Cow<'a, str>, D::Error> {
-    struct CowStrVisitor;
+use crate::{
+    error::Error,
+    seq::VecSeedVisitor,
+    string::{trim, trim_in_place},
+};
 
-    impl<'a> de::Visitor<'a> for CowStrVisitor {
-        type Value = Cow<'a, str>;
+/// Visitor that trims a CoW string, borrowing from the input where possible.
+pub(crate) struct CowStrVisitor {
+    /// Whether to return error if the string ends up empty.
+    pub(crate) non_empty: bool,
+}
 
-        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl CowStrVisitor {
+    /// Returns error if `val` is rejected for being empty after trimming.
+    fn check<E: de::Error>(&self, val: &str) -> Result<(), E> {
+        if self.non_empty && val.trim().is_empty() {
+            return Err(Error::Empty {
+                original: val.to_owned(),
+                index: None,
+            }
+            .into_de_error());
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> de::Visitor<'a> for CowStrVisitor {
+    type Value = Cow<'a, str>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.non_empty {
+            formatter.write_str("a non-empty string")
+        } else {
             formatter.write_str("a string")
         }
+    }
 
-        fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
-            Ok(Cow::Owned(val.trim().to_owned()))
+    fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+        self.check(val)?;
+        Ok(Cow::Owned(trim(val).to_owned()))
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, val: &'a str) -> Result<Self::Value, E> {
+        self.check(val)?;
+        Ok(Cow::Borrowed(trim(val)))
+    }
+
+    fn visit_string<E: de::Error>(self, mut val: String) -> Result<Self::Value, E> {
+        self.check(&val)?;
+        trim_in_place(&mut val);
+        Ok(Cow::Owned(val))
+    }
+
+    fn visit_bytes<E: de::Error>(self, val: &[u8]) -> Result<Self::Value, E> {
+        match str::from_utf8(val) {
+            Ok(val) => self.visit_str(val),
+            Err(_) => Err(de::Error::invalid_value(de::Unexpected::Bytes(val), &self)),
         }
+    }
 
-        fn visit_borrowed_str<E: de::Error>(self, val: &'a str) -> Result<Self::Value, E> {
-            Ok(Cow::Borrowed(val.trim()))
+    fn visit_borrowed_bytes<E: de::Error>(self, val: &'a [u8]) -> Result<Self::Value, E> {
+        match str::from_utf8(val) {
+            Ok(val) => self.visit_borrowed_str(val),
+            Err(_) => Err(de::Error::invalid_value(de::Unexpected::Bytes(val), &self)),
         }
+    }
 
-        fn visit_string<E: de::Error>(self, val: String) -> Result<Self::Value, E> {
-            Ok(Cow::Owned(val.trim().to_owned()))
+    fn visit_byte_buf<E: de::Error>(self, val: Vec<u8>) -> Result<Self::Value, E> {
+        match String::from_utf8(val) {
+            Ok(val) => self.visit_string(val),
+            Err(err) => Err(de::Error::invalid_value(
+                de::Unexpected::Bytes(&err.into_bytes()),
+                &self,
+            )),
         }
+    }
+}
 
-        fn visit_bytes<E: de::Error>(self, val: &[u8]) -> Result<Self::Value, E> {
-            match str::from_utf8(val) {
-                Ok(val) => Ok(Cow::Owned(val.trim().to_owned())),
-                Err(_) => Err(de::Error::invalid_value(de::Unexpected::Bytes(val), &self)),
-            }
+/// Trims a CoW string during deserialization.
+pub fn cow_str<'a, 'de: 'a, D: Deserializer<'de>>(de: D) -> Result<Cow<'a, str>, D::Error> {
+    de.deserialize_str(CowStrVisitor { non_empty: false })
+}
+
+/// Trims an optional CoW string during deserialization.
+pub fn option_cow_str<'a, 'de: 'a, D: Deserializer<'de>>(
+    de: D,
+) -> Result<Option<Cow<'a, str>>, D::Error> {
+    struct OptionCowStrVisitor;
+
+    impl<'a> de::Visitor<'a> for OptionCowStrVisitor {
+        type Value = Option<Cow<'a, str>>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("an optional string")
         }
 
-        fn visit_borrowed_bytes<E: de::Error>(self, val: &'a [u8]) -> Result<Self::Value, E> {
-            match str::from_utf8(val) {
-                Ok(val) => Ok(Cow::Borrowed(val.trim())),
-                Err(_) => Err(de::Error::invalid_value(de::Unexpected::Bytes(val), &self)),
-            }
+        fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
         }
 
-        fn visit_byte_buf<E: de::Error>(self, val: Vec<u8>) -> Result<Self::Value, E> {
-            match String::from_utf8(val) {
-                Ok(val) => Ok(Cow::Owned(val.trim().to_owned())),
-                Err(err) => Err(de::Error::invalid_value(
-                    de::Unexpected::Bytes(&err.into_bytes()),
-                    &self,
-                )),
-            }
+        fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D: Deserializer<'a>>(self, de: D) -> Result<Self::Value, D::Error> {
+            cow_str(de).map(Some)
+        }
+    }
+
+    de.deserialize_option(OptionCowStrVisitor)
+}
+
+/// Trims list of CoW strings during deserialization.
+pub fn vec_cow_str<'a, 'de: 'a, D: Deserializer<'de>>(
+    de: D,
+) -> Result<Vec<Cow<'a, str>>, D::Error> {
+    struct CowStrSeed;
+
+    impl<'a> de::DeserializeSeed<'a> for CowStrSeed {
+        type Value = Cow<'a, str>;
+
+        fn deserialize<D: Deserializer<'a>>(self, de: D) -> Result<Self::Value, D::Error> {
+            cow_str(de)
         }
     }
 
-    de.deserialize_str(CowStrVisitor)
+    de.deserialize_seq(VecSeedVisitor::new("a sequence of strings", |_| CowStrSeed))
 }
 
 #[cfg(test)]
@@ -138,4 +217,57 @@ mod tests {
         assert!(matches!(&json.foo, Cow::Owned(_)));
         assert_eq!(json.foo, "b\\ar");
     }
+
+    #[test]
+    fn option_cow_str() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo<'a> {
+            #[serde(borrow, deserialize_with = "super::option_cow_str")]
+            foo: Option<Cow<'a, str>>,
+        }
+
+        serde_json::from_str::<Foo<'static>>(r#"{ "foo": 1 }"#).unwrap_err();
+
+        let json = serde_json::from_slice::<Foo<'_>>(br#"{ "foo": null }"#).unwrap();
+        assert_eq!(json.foo, None);
+
+        let json = serde_json::from_slice::<Foo<'_>>(br#"{ "foo": " " }"#).unwrap();
+        assert_eq!(json.foo.as_deref(), Some(""));
+
+        // borrowed and trimmed
+        let source = br#"{ "foo": " bar " }"#.to_vec();
+        let json = serde_json::from_slice::<Foo<'_>>(&source).unwrap();
+        assert!(matches!(&json.foo, Some(Cow::Borrowed("bar"))));
+
+        // owned and trimmed when escape sequences need processing
+        let source = br#"{ "foo": " b\\ar " }"#.to_vec();
+        let json = serde_json::from_slice::<Foo<'_>>(&source).unwrap();
+        assert!(matches!(&json.foo, Some(Cow::Owned(_))));
+        assert_eq!(json.foo.as_deref(), Some("b\\ar"));
+    }
+
+    #[test]
+    fn vec_cow_str() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo<'a> {
+            #[serde(borrow, deserialize_with = "super::vec_cow_str")]
+            foo: Vec<Cow<'a, str>>,
+        }
+
+        serde_json::from_str::<Foo<'static>>(r#"{ "foo": 1 }"#).unwrap_err();
+        serde_json::from_str::<Foo<'static>>(r#"{ "foo": "" }"#).unwrap_err();
+        serde_json::from_str::<Foo<'static>>(r#"{ "foo": [1] }"#).unwrap_err();
+
+        let json = serde_json::from_slice::<Foo<'_>>(br#"{ "foo": [] }"#).unwrap();
+        assert!(json.foo.is_empty());
+
+        let source = br#"{ "foo": [" bar ", "baz", " b\\az "] }"#.to_vec();
+        let json = serde_json::from_slice::<Foo<'_>>(&source).unwrap();
+        assert_eq!(json.foo, ["bar", "baz", "b\\az"]);
+
+        // borrowed when possible, owned when escape sequences need processing
+        assert!(matches!(&json.foo[0], Cow::Borrowed(_)));
+        assert!(matches!(&json.foo[1], Cow::Borrowed(_)));
+        assert!(matches!(&json.foo[2], Cow::Owned(_)));
+    }
 }
@@ -0,0 +1,633 @@
+//! A trimming deserializer for environment variables.
+//!
+//! [`from_env()`] deserializes a type from the process environment variables that start with a
+//! prefix. Values from secret mounts and `.env` files often carry stray newlines and spaces, so
+//! every value is trimmed before being used.
+//!
+//! Variable names are mapped to fields as follows:
+//!
+//! - the prefix is removed and the rest of the name is lowercased, so `APP_LOG_LEVEL` with the
+//!   prefix `APP_` becomes the field `log_level`;
+//! - a double underscore separates nested fields, so `APP_DB__HOST` becomes the field `host` of the
+//!   field `db`.
+//!
+//! Values are parsed into booleans, numbers, and chars using their [`FromStr`](core::str::FromStr)
+//! implementations. Sequences are read from comma-separated values, with each part trimmed, and a
+//! value that is empty after trimming produces an empty sequence. Optional fields are `None` when
+//! the variable is missing or empty after trimming.
+//!
+//! Use [`from_vars()`] to deserialize from any list of variables instead, like in tests. Only
+//! [`from_env()`] requires the `std` crate feature.
+//!
+//! # Examples
+//!
+//! ```
+//! #[derive(Debug, serde::Deserialize)]
+//! struct Config {
+//!     log_level: String,
+//!     db: Db,
+//!     features: Vec<String>,
+//! }
+//!
+//! #[derive(Debug, serde::Deserialize)]
+//! struct Db {
+//!     host: String,
+//!     port: u16,
+//! }
+//!
+//! let config = detrim::env::from_vars::<Config, _, _, _>(
+//!     "APP_",
+//!     [
+//!         ("APP_LOG_LEVEL", "debug\n"),
+//!         ("APP_DB__HOST", " localhost "),
+//!         ("APP_DB__PORT", "5432\n"),
+//!         ("APP_FEATURES", "search, billing"),
+//!         ("HOME", "/root"),
+//!     ],
+//! )
+//! .unwrap();
+//! assert_eq!(config.log_level, "debug");
+//! assert_eq!(config.db.host, "localhost");
+//! assert_eq!(config.db.port, 5432);
+//! assert_eq!(config.features, ["search", "billing"]);
+//! ```
+
+use alloc::{
+    collections::{btree_map, BTreeMap},
+    string::{String, ToString as _},
+    vec::Vec,
+};
+use core::fmt;
+
+use serde::{
+    de::{self, value::SeqDeserializer, DeserializeOwned, Error as _, IntoDeserializer},
+    forward_to_deserialize_any,
+};
+
+use crate::{
+    parse::trim_parse,
+    string::{trim, trim_in_place},
+};
+
+/// Error returned when deserializing from environment variables fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    var: Option<String>,
+    msg: String,
+}
+
+impl Error {
+    /// Returns the name of the variable that caused the error, if known.
+    pub fn var(&self) -> Option<&str> {
+        self.var.as_deref()
+    }
+
+    /// Attributes the error to `var`, unless it is already attributed to a variable.
+    fn in_var(mut self, var: &str) -> Self {
+        if self.var.is_none() {
+            self.var = Some(var.into());
+        }
+
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.var {
+            Some(var) => write!(f, "{var}: {}", self.msg),
+            None => f.write_str(&self.msg),
+        }
+    }
+}
+
+impl de::StdError for Error {}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self {
+            var: None,
+            msg: msg.to_string(),
+        }
+    }
+}
+
+/// Deserializes `T` from the process environment variables that start with `prefix`.
+///
+/// See [module docs](self) for more.
+#[cfg(feature = "std")]
+pub fn from_env<T: DeserializeOwned>(prefix: &str) -> Result<T, Error> {
+    T::deserialize(Deserializer::from_env(prefix)?)
+}
+
+/// Deserializes `T` from the `vars` that start with `prefix`.
+///
+/// See [module docs](self) for more.
+pub fn from_vars<T, I, K, V>(prefix: &str, vars: I) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+    I: IntoIterator<Item = (K, V)>,
+    K: Into<String>,
+    V: Into<String>,
+{
+    T::deserialize(Deserializer::from_vars(prefix, vars)?)
+}
+
+/// A variable's trimmed value, or a group of nested fields.
+enum Node {
+    Value { var: String, val: String },
+    Map(BTreeMap<String, Node>),
+}
+
+/// Deserializer over environment variables.
+///
+/// See [module docs](self) for more.
+pub struct Deserializer {
+    map: BTreeMap<String, Node>,
+}
+
+impl fmt::Debug for Deserializer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Deserializer").finish_non_exhaustive()
+    }
+}
+
+impl Deserializer {
+    /// Creates a deserializer over the process environment variables that start with `prefix`.
+    ///
+    /// Variables whose names are not valid unicode are ignored. Returns an error if the value of
+    /// a matching variable is not valid unicode.
+    #[cfg(feature = "std")]
+    pub fn from_env(prefix: &str) -> Result<Self, Error> {
+        let mut vars = Vec::new();
+
+        for (var, val) in std::env::vars_os() {
+            let Some(var) = var.to_str().filter(|var| var.starts_with(prefix)) else {
+                continue;
+            };
+
+            let Ok(val) = val.into_string() else {
+                return Err(Error::custom("invalid value: not valid unicode").in_var(var));
+            };
+
+            vars.push((var.to_owned(), val));
+        }
+
+        Self::from_vars(prefix, vars)
+    }
+
+    /// Creates a deserializer over the `vars` that start with `prefix`.
+    ///
+    /// Returns an error if a variable name has an empty nested field, like `APP_DB__`, or if two
+    /// names map to the same field.
+    pub fn from_vars<I, K, V>(prefix: &str, vars: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let mut root = BTreeMap::new();
+
+        for (var, val) in vars {
+            let var: String = var.into();
+
+            let Some(name) = var.strip_prefix(prefix).filter(|name| !name.is_empty()) else {
+                continue;
+            };
+
+            let mut val: String = val.into();
+            trim_in_place(&mut val);
+
+            let path = name.split("__").map(str::to_lowercase).collect::<Vec<_>>();
+            insert(&mut root, &path, var, val)?;
+        }
+
+        Ok(Self { map: root })
+    }
+}
+
+/// Inserts the value of `var` into `root` at `path`.
+fn insert(
+    root: &mut BTreeMap<String, Node>,
+    path: &[String],
+    var: String,
+    val: String,
+) -> Result<(), Error> {
+    if path.iter().any(String::is_empty) {
+        return Err(Error::custom("invalid name: empty nested field").in_var(&var));
+    }
+
+    let conflict =
+        |var: &str, other: &str| Error::custom(format_args!("conflicts with {other}")).in_var(var);
+
+    let (field, parents) = path.split_last().expect("split always yields a part");
+    let mut map = root;
+
+    for parent in parents {
+        let node = map
+            .entry(parent.clone())
+            .or_insert_with(|| Node::Map(BTreeMap::new()));
+
+        map = match node {
+            Node::Map(map) => map,
+            Node::Value { var: other, .. } => return Err(conflict(&var, other)),
+        };
+    }
+
+    match map.entry(field.clone()) {
+        btree_map::Entry::Vacant(entry) => {
+            entry.insert(Node::Value { var, val });
+            Ok(())
+        }
+        btree_map::Entry::Occupied(entry) => match entry.get() {
+            Node::Value { var: other, .. } => Err(conflict(&var, other)),
+            Node::Map(map) => {
+                let other = first_var(map).unwrap_or_default();
+                Err(conflict(&var, other))
+            }
+        },
+    }
+}
+
+/// Returns the name of any variable nested in `map`.
+fn first_var(map: &BTreeMap<String, Node>) -> Option<&str> {
+    map.values().find_map(|node| match node {
+        Node::Value { var, .. } => Some(var.as_str()),
+        Node::Map(map) => first_var(map),
+    })
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(MapAccess {
+            iter: self.map.into_iter(),
+            node: None,
+        })
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf unit
+        unit_struct seq tuple tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// Map access over a group of nested fields.
+struct MapAccess {
+    iter: btree_map::IntoIter<String, Node>,
+    node: Option<Node>,
+}
+
+impl<'de> de::MapAccess<'de> for MapAccess {
+    type Error = Error;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let Some((field, node)) = self.iter.next() else {
+            return Ok(None);
+        };
+
+        self.node = Some(node);
+        seed.deserialize(field.into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.node.take() {
+            Some(Node::Value { var, val }) => seed
+                .deserialize(ValueDeserializer { val })
+                .map_err(|err| err.in_var(&var)),
+            Some(Node::Map(map)) => seed.deserialize(Deserializer { map }),
+            None => Err(Error::custom("value is missing")),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+/// Deserializer for a single trimmed value.
+struct ValueDeserializer {
+    val: String,
+}
+
+impl<'de> IntoDeserializer<'de, Error> for ValueDeserializer {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+macro_rules! deserialize_parse {
+    ($($method:ident => $visit:ident),* $(,)?) => {
+        $(
+            fn $method<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                visitor.$visit(trim_parse(&self.val)?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_string(self.val)
+    }
+
+    deserialize_parse! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_bytes<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_byte_buf(self.val.into_bytes())
+    }
+
+    fn deserialize_byte_buf<V: de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.val.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let parts = match self.val.as_str() {
+            "" => Vec::new(),
+            val => val.split(',').map(trim).map(String::from).collect(),
+        };
+
+        let mut seq = SeqDeserializer::new(parts.into_iter().map(|val| ValueDeserializer { val }));
+        let val = visitor.visit_seq(&mut seq)?;
+        seq.end()?;
+
+        Ok(val)
+    }
+
+    fn deserialize_tuple<V: de::Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_enum(self.val.into_deserializer())
+    }
+
+    fn deserialize_ignored_any<V: de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        str string map struct identifier
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{borrow::ToOwned as _, vec};
+
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Config {
+        name: String,
+        db: Db,
+        #[serde(default)]
+        tags: Vec<String>,
+        debug: Option<bool>,
+        mode: Option<Mode>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Db {
+        host: String,
+        port: u16,
+        timeout: Option<f64>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Mode {
+        Fast,
+        Safe,
+    }
+
+    #[test]
+    fn from_vars() {
+        let config = super::from_vars::<Config, _, _, _>(
+            "APP_",
+            [
+                ("APP_NAME", "  crab\n"),
+                ("APP_DB__HOST", "localhost\r\n"),
+                ("APP_DB__PORT", " 5432 "),
+                ("APP_DB__TIMEOUT", "1.5"),
+                ("APP_TAGS", " a, b ,c "),
+                ("APP_DEBUG", "true\n"),
+                ("APP_MODE", " safe"),
+                ("OTHER_NAME", "ignored"),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            config,
+            Config {
+                name: "crab".to_owned(),
+                db: Db {
+                    host: "localhost".to_owned(),
+                    port: 5432,
+                    timeout: Some(1.5),
+                },
+                tags: vec!["a".to_owned(), "b".to_owned(), "c".to_owned()],
+                debug: Some(true),
+                mode: Some(Mode::Safe),
+            },
+        );
+
+        let config = super::from_vars::<Config, _, _, _>(
+            "APP_",
+            [
+                ("APP_NAME", "crab"),
+                ("APP_DB__HOST", "localhost"),
+                ("APP_DB__PORT", "5432"),
+                ("APP_DB__TIMEOUT", " "),
+                ("APP_TAGS", "\n"),
+                ("APP_MODE", "fast"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(config.db.timeout, None);
+        assert!(config.tags.is_empty());
+        assert_eq!(config.debug, None);
+        assert_eq!(config.mode, Some(Mode::Fast));
+    }
+
+    #[test]
+    fn errors() {
+        let err = super::from_vars::<Config, _, _, _>(
+            "APP_",
+            [
+                ("APP_NAME", "crab"),
+                ("APP_DB__HOST", "localhost"),
+                ("APP_DB__PORT", "port"),
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(err.var(), Some("APP_DB__PORT"));
+        assert!(err
+            .to_string()
+            .starts_with("APP_DB__PORT: invalid value: string \"port\""));
+
+        let err = super::from_vars::<Config, _, _, _>("APP_", [("APP_NAME", "crab")]).unwrap_err();
+        assert_eq!(err.var(), None);
+        assert!(err.to_string().contains("missing field `db`"));
+
+        let err = super::from_vars::<Config, _, _, _>(
+            "APP_",
+            [("APP_DB", "localhost"), ("APP_DB__HOST", "localhost")],
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "APP_DB__HOST: conflicts with APP_DB");
+
+        let err = super::from_vars::<Config, _, _, _>(
+            "APP_",
+            [("APP_DB__HOST", "localhost"), ("APP_DB", "localhost")],
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "APP_DB: conflicts with APP_DB__HOST");
+
+        let err = super::from_vars::<Config, _, _, _>("APP_", [("APP_DB__", "x")]).unwrap_err();
+        assert_eq!(err.var(), Some("APP_DB__"));
+    }
+
+    #[test]
+    fn collections() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Foo {
+            ports: Vec<u16>,
+            pair: (String, u8),
+            labels: BTreeMap<String, String>,
+        }
+
+        let foo = super::from_vars::<Foo, _, _, _>(
+            "",
+            [
+                ("PORTS", "80, 443"),
+                ("PAIR", "a ,1"),
+                ("LABELS__TEAM", " core "),
+                ("LABELS__TIER", "1\n"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(foo.ports, [80, 443]);
+        assert_eq!(foo.pair, ("a".to_owned(), 1));
+        assert_eq!(foo.labels["team"], "core");
+        assert_eq!(foo.labels["tier"], "1");
+
+        let err = super::from_vars::<Foo, _, _, _>(
+            "",
+            [("PORTS", ""), ("PAIR", "a,1,2"), ("LABELS__TEAM", "")],
+        )
+        .unwrap_err();
+        assert_eq!(err.var(), Some("PAIR"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn from_env() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Package {
+            name: String,
+            version_major: u64,
+        }
+
+        // set by Cargo when running tests
+        let package = super::from_env::<Package>("CARGO_PKG_").unwrap();
+        assert_eq!(package.name, env!("CARGO_PKG_NAME"));
+        assert_eq!(
+            package.version_major.to_string(),
+            env!("CARGO_PKG_VERSION_MAJOR"),
+        );
+    }
+}
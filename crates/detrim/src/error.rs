@@ -0,0 +1,751 @@
+//! Structured errors returned by this crate's deserializers.
+//!
+//! Deserializer functions must return the deserializer's own error type, so detrim's errors reach
+//! callers through [`de::Error::invalid_value()`] or [`de::Error::invalid_length()`], built from
+//! an [`Error`]. Messages include the value as it was before trimming, escaped and truncated. For
+//! example, a blank string rejected by [`string_non_empty()`](crate::string_non_empty) produces:
+//!
+//! ```text
+//! invalid value: string "   ", expected non-empty string
+//! ```
+//!
+//! Behind the `serde_path_to_error` crate feature, [`deserialize()`] also tracks the path to the
+//! field that failed, and recovers the structured [`Error`] so that callers can match on it, like
+//! when building localized form validation messages.
+
+use alloc::{format, string::String};
+use core::fmt;
+
+use serde::de;
+#[cfg(feature = "serde_path_to_error")]
+use serde::{Deserialize, Deserializer};
+
+use crate::string::is_trim_whitespace;
+#[cfg(feature = "serde_path_to_error")]
+use crate::wrap::Wrap;
+
+/// Number of chars of a value shown in error messages before it is truncated.
+const MAX_SHOWN_CHARS: usize = 40;
+
+/// Reason that a value was rejected by one of this crate's deserializers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// String was empty after trimming.
+    Empty {
+        /// Value before trimming.
+        original: String,
+        /// Index of the value, if it was an item in a sequence.
+        index: Option<usize>,
+    },
+
+    /// Sequence had no non-empty items.
+    NoItems,
+
+    /// Length of trimmed string was out of bounds.
+    Length {
+        /// Value before trimming.
+        original: String,
+        /// Length of the trimmed value.
+        len: usize,
+        /// Minimum allowed length.
+        min: usize,
+        /// Maximum allowed length.
+        max: usize,
+        /// Plural name of the unit of length, like `"chars"`.
+        unit: &'static str,
+    },
+
+    /// Trimmed string could not be parsed.
+    Parse {
+        /// Value before trimming.
+        original: String,
+        /// Name of the type being parsed.
+        ty: &'static str,
+        /// Message of the parse error.
+        reason: String,
+    },
+
+    /// String had leading or trailing whitespace where it is not allowed.
+    Untrimmed {
+        /// Value as received.
+        original: String,
+        /// Index of the value, if it was an item in a sequence.
+        index: Option<usize>,
+    },
+
+    /// Path started with `~`, but the home directory it expands to was unknown.
+    NoHomeDir {
+        /// Path before trimming.
+        original: String,
+    },
+
+    /// Two items of a sequence were equal after trimming.
+    Duplicate {
+        /// Trimmed value of the items.
+        value: String,
+        /// Index of the first item.
+        first: usize,
+        /// Index of the second item.
+        index: usize,
+    },
+
+    /// Two keys of a map were equal after trimming.
+    DuplicateKey {
+        /// First key, before trimming.
+        first: String,
+        /// Second key, before trimming.
+        second: String,
+    },
+}
+
+impl Error {
+    /// Returns the value before trimming, if the error relates to a single value.
+    pub fn original(&self) -> Option<&str> {
+        match self {
+            Self::Empty { original, .. }
+            | Self::Length { original, .. }
+            | Self::Parse { original, .. }
+            | Self::Untrimmed { original, .. }
+            | Self::NoHomeDir { original } => Some(original),
+            Self::NoItems | Self::Duplicate { .. } | Self::DuplicateKey { .. } => None,
+        }
+    }
+
+    /// Converts into a deserializer error, recording the structured error for [`deserialize()`].
+    pub(crate) fn into_de_error<E: de::Error>(self) -> E {
+        #[cfg(feature = "serde_path_to_error")]
+        return capture::raise(&self, || self.to_de_error());
+
+        #[cfg(not(feature = "serde_path_to_error"))]
+        self.to_de_error()
+    }
+
+    /// Builds a deserializer error using the constructor that fits the variant.
+    ///
+    /// Rejected strings are passed as the [`de::Unexpected`] value, so formats that rewrite
+    /// `invalid_value` and `invalid_length` errors see them as such.
+    fn to_de_error<E: de::Error>(&self) -> E {
+        let expected = Expected(self);
+
+        match self {
+            Self::Empty { original, .. }
+            | Self::Parse { original, .. }
+            | Self::Untrimmed { original, .. }
+            | Self::NoHomeDir { original } => {
+                with_unexpected(original, |unexp| E::invalid_value(unexp, &expected))
+            }
+            Self::NoItems => E::invalid_length(0, &expected),
+            Self::Length { len, .. } => E::invalid_length(*len, &expected),
+            Self::Duplicate {
+                value,
+                first,
+                index,
+            } => E::invalid_value(
+                de::Unexpected::Other(&format!(
+                    "duplicate string {} at indices {first} and {index} after trimming",
+                    Shown(value),
+                )),
+                &expected,
+            ),
+            Self::DuplicateKey { first, second } => E::invalid_value(
+                de::Unexpected::Other(&format!(
+                    "keys {} and {} are equal after trimming",
+                    Shown(first),
+                    Shown(second),
+                )),
+                &expected,
+            ),
+        }
+    }
+}
+
+/// Calls `f` with `original` as an unexpected string, truncated to [`MAX_SHOWN_CHARS`].
+fn with_unexpected<R>(original: &str, f: impl FnOnce(de::Unexpected<'_>) -> R) -> R {
+    if original.chars().nth(MAX_SHOWN_CHARS).is_none() {
+        return f(de::Unexpected::Str(original));
+    }
+
+    f(de::Unexpected::Other(&format!(
+        "string {}",
+        Shown(original)
+    )))
+}
+
+/// Formats a value as an escaped string literal, truncated to [`MAX_SHOWN_CHARS`].
+struct Shown<'a>(&'a str);
+
+impl fmt::Display for Shown<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0.char_indices().nth(MAX_SHOWN_CHARS) {
+            Some((idx, _)) => write!(f, "{:?}...", &self.0[..idx]),
+            None => write!(f, "{:?}", self.0),
+        }
+    }
+}
+
+/// Writes which side of `original` has whitespace, showing the offending characters.
+fn write_whitespace(f: &mut fmt::Formatter<'_>, original: &str) -> fmt::Result {
+    let rest = original.trim_start_matches(is_trim_whitespace);
+    let leading = &original[..original.len() - rest.len()];
+    let trailing = &rest[rest.trim_end_matches(is_trim_whitespace).len()..];
+
+    match (leading, trailing) {
+        _ if rest.is_empty() && !original.is_empty() => f.write_str(" (whitespace-only string)"),
+        ("", "") => Ok(()),
+        (leading, "") => write!(f, " (found leading whitespace {})", Shown(leading)),
+        ("", trailing) => write!(f, " (found trailing whitespace {})", Shown(trailing)),
+        (leading, trailing) => write!(
+            f,
+            " (found leading whitespace {} and trailing whitespace {})",
+            Shown(leading),
+            Shown(trailing),
+        ),
+    }
+}
+
+/// Describes what was expected instead of the value rejected with an [`Error`].
+struct Expected<'a>(&'a Error);
+
+impl de::Expected for Expected<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let index = match self.0 {
+            Error::Empty { index, .. } => {
+                f.write_str("non-empty string")?;
+                index
+            }
+            Error::Untrimmed { original, index } => {
+                f.write_str(crate::strict::EXPECTED)?;
+
+                if let Some(idx) = index {
+                    write!(f, " at index {idx}")?;
+                }
+
+                return write_whitespace(f, original);
+            }
+            Error::NoItems => return f.write_str("at least one non-empty string"),
+            Error::Length {
+                original,
+                min,
+                max,
+                unit,
+                ..
+            } => {
+                write!(f, "a trimmed string of {min} to {max} {unit}")?;
+
+                if crate::string::is_untrimmed(original) {
+                    write!(f, " (original value: {})", Shown(original))?;
+                }
+
+                return Ok(());
+            }
+            Error::Parse { ty, reason, .. } => {
+                return write!(f, "a string containing a {ty} ({reason})");
+            }
+            Error::NoHomeDir { .. } => {
+                return f
+                    .write_str("a path without a leading `~`, as the home directory is unknown");
+            }
+            Error::Duplicate { .. } => return f.write_str("unique strings"),
+            Error::DuplicateKey { .. } => return f.write_str("unique keys"),
+        };
+
+        match index {
+            Some(idx) => write!(f, " at index {idx}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_de_error::<de::value::Error>(), f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Error returned by [`deserialize()`], with the path to the field that failed.
+#[cfg(feature = "serde_path_to_error")]
+#[derive(Debug)]
+pub struct PathError<E> {
+    inner: serde_path_to_error::Error<E>,
+    error: Option<Error>,
+}
+
+#[cfg(feature = "serde_path_to_error")]
+impl<E> PathError<E> {
+    /// Returns the path to the field that failed.
+    pub fn path(&self) -> &serde_path_to_error::Path {
+        self.inner.path()
+    }
+
+    /// Returns the deserializer's error.
+    pub fn inner(&self) -> &E {
+        self.inner.inner()
+    }
+
+    /// Returns the deserializer's error.
+    pub fn into_inner(self) -> E {
+        self.inner.into_inner()
+    }
+
+    /// Returns the structured error, if the failure was caused by one of this crate's
+    /// deserializers.
+    pub fn error(&self) -> Option<&Error> {
+        self.error.as_ref()
+    }
+}
+
+#[cfg(feature = "serde_path_to_error")]
+impl<E: fmt::Display> fmt::Display for PathError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.inner, f)
+    }
+}
+
+#[cfg(feature = "serde_path_to_error")]
+impl<E: std::error::Error> std::error::Error for PathError<E> {}
+
+/// Deserializes `T`, tracking the path to the field that failed and any structured [`Error`].
+///
+/// The structured error travels inside the deserializer's error, so it is reported only when the
+/// error that ended deserialization was raised by one of this crate's deserializers. Errors that
+/// are raised and recovered from, like when trying the variants of an untagged enum, are not
+/// reported.
+///
+/// # Examples
+///
+/// ```
+/// use detrim::error::Error;
+///
+/// #[derive(Debug, serde::Deserialize)]
+/// struct Form {
+///     user: User,
+/// }
+///
+/// #[derive(Debug, serde::Deserialize)]
+/// struct User {
+///     #[serde(deserialize_with = "detrim::string_non_empty")]
+///     name: String,
+/// }
+///
+/// let mut de = serde_json::Deserializer::from_str(r#"{ "user": { "name": "   " } }"#);
+/// let err = detrim::error::deserialize::<Form, _>(&mut de).unwrap_err();
+///
+/// assert_eq!(err.path().to_string(), "user.name");
+/// assert!(matches!(
+///     err.error(),
+///     Some(Error::Empty { original, .. }) if original == "   ",
+/// ));
+/// ```
+#[cfg(feature = "serde_path_to_error")]
+pub fn deserialize<'de, T, D>(de: D) -> Result<T, PathError<D::Error>>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    capture::clear_pending();
+
+    serde_path_to_error::deserialize(Wrap::<_, capture::Capture>::new(de)).map_err(|err| {
+        let path = err.path().clone();
+        let capture::CaptureError { inner, error } = err.into_inner();
+
+        PathError {
+            inner: serde_path_to_error::Error::new(path, inner),
+            error,
+        }
+    })
+}
+
+/// Wrapper layer whose errors carry the structured [`Error`] they were raised with.
+///
+/// Values deserialized through the [`Capture`] layer see [`CaptureError`] as the error type, so
+/// errors built by [`Error::into_de_error()`] pick up the structured error as they are
+/// constructed. Where an error has to pass through the wrapped deserializer, as its own error
+/// type, the structured error is set aside in a thread-local slot and picked up again as soon as
+/// that deserializer returns.
+#[cfg(feature = "serde_path_to_error")]
+mod capture {
+    use core::{cell::Cell, fmt};
+
+    use serde::de;
+
+    use super::Error;
+    use crate::wrap::Layer;
+
+    std::thread_local! {
+        /// Error currently being converted by [`Error::into_de_error()`].
+        static RAISING: Cell<Option<Error>> = const { Cell::new(None) };
+
+        /// Error set aside while its deserializer error passes through a wrapped deserializer.
+        static PENDING: Cell<Option<Error>> = const { Cell::new(None) };
+    }
+
+    /// Calls `f` to build a deserializer error for `error`, making `error` available to
+    /// [`CaptureError`] constructors called by `f`.
+    pub(super) fn raise<E>(error: &Error, f: impl FnOnce() -> E) -> E {
+        /// Clears the error being raised, even if `f` panics.
+        struct Guard;
+
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                RAISING.with(Cell::take);
+            }
+        }
+
+        RAISING.with(|raising| raising.set(Some(error.clone())));
+        let _guard = Guard;
+        f()
+    }
+
+    /// Clears any error left set aside by a previous, panicked deserialization.
+    pub(super) fn clear_pending() {
+        PENDING.with(Cell::take);
+    }
+
+    /// Unwraps an error to pass it through a wrapped deserializer, setting its structured error
+    /// aside.
+    fn set_aside<E>(err: CaptureError<E>) -> E {
+        PENDING.with(|pending| pending.set(err.error));
+        err.inner
+    }
+
+    /// Wraps the result of calling into a wrapped deserializer, picking up any structured error
+    /// set aside while it ran.
+    ///
+    /// The slot is cleared even on success, in case the deserializer recovered from the error.
+    fn pick_up<T, E>(res: Result<T, E>) -> Result<T, CaptureError<E>> {
+        let error = PENDING.with(Cell::take);
+
+        res.map_err(|inner| CaptureError { inner, error })
+    }
+
+    /// Deserializer error carrying the structured error it was raised with, if any.
+    #[derive(Debug)]
+    pub(super) struct CaptureError<E> {
+        pub(super) inner: E,
+        pub(super) error: Option<Error>,
+    }
+
+    impl<E> CaptureError<E> {
+        fn new(inner: E) -> Self {
+            Self {
+                inner,
+                error: RAISING.with(Cell::take),
+            }
+        }
+    }
+
+    impl<E: fmt::Display> fmt::Display for CaptureError<E> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt::Display::fmt(&self.inner, f)
+        }
+    }
+
+    impl<E: de::Error> de::StdError for CaptureError<E> {}
+
+    impl<E: de::Error> de::Error for CaptureError<E> {
+        fn custom<T: fmt::Display>(msg: T) -> Self {
+            Self::new(E::custom(msg))
+        }
+
+        fn invalid_type(unexp: de::Unexpected<'_>, exp: &dyn de::Expected) -> Self {
+            Self::new(E::invalid_type(unexp, exp))
+        }
+
+        fn invalid_value(unexp: de::Unexpected<'_>, exp: &dyn de::Expected) -> Self {
+            Self::new(E::invalid_value(unexp, exp))
+        }
+
+        fn invalid_length(len: usize, exp: &dyn de::Expected) -> Self {
+            Self::new(E::invalid_length(len, exp))
+        }
+
+        fn unknown_variant(variant: &str, expected: &'static [&'static str]) -> Self {
+            Self::new(E::unknown_variant(variant, expected))
+        }
+
+        fn unknown_field(field: &str, expected: &'static [&'static str]) -> Self {
+            Self::new(E::unknown_field(field, expected))
+        }
+
+        fn missing_field(field: &'static str) -> Self {
+            Self::new(E::missing_field(field))
+        }
+
+        fn duplicate_field(field: &'static str) -> Self {
+            Self::new(E::duplicate_field(field))
+        }
+    }
+
+    /// Wrapper layer which carries structured errors in [`CaptureError`]s.
+    pub(super) struct Capture;
+
+    impl Layer for Capture {
+        type Error<E: de::Error> = CaptureError<E>;
+
+        const TRACK_PATHS: bool = false;
+
+        fn lift<T, E: de::Error>(res: Result<T, E>) -> Result<T, CaptureError<E>> {
+            pick_up(res)
+        }
+
+        fn lower<E: de::Error>(err: CaptureError<E>) -> E {
+            set_aside(err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{borrow::ToOwned as _, string::ToString as _};
+
+    use super::*;
+
+    #[test]
+    fn display() {
+        let err = Error::Empty {
+            original: "  ".to_owned(),
+            index: None,
+        };
+        assert_eq!(
+            err.to_string(),
+            r#"invalid value: string "  ", expected non-empty string"#,
+        );
+
+        let err = Error::Empty {
+            original: String::new(),
+            index: Some(2),
+        };
+        assert_eq!(
+            err.to_string(),
+            r#"invalid value: string "", expected non-empty string at index 2"#,
+        );
+
+        let err = Error::Parse {
+            original: " 1x\n".to_owned(),
+            ty: "u8",
+            reason: "invalid digit found in string".to_owned(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "invalid value: string \" 1x\\n\", expected a string containing a u8 (invalid digit \
+             found in string)",
+        );
+
+        let err = Error::Untrimmed {
+            original: "   ".to_owned(),
+            index: Some(1),
+        };
+        assert_eq!(
+            err.to_string(),
+            "invalid value: string \"   \", expected a string without leading or trailing \
+             whitespace at index 1 (whitespace-only string)",
+        );
+    }
+
+    #[test]
+    fn truncation() {
+        let err = Error::Length {
+            original: alloc::format!(" {} ", "a".repeat(50)),
+            len: 50,
+            min: 1,
+            max: 10,
+            unit: "bytes",
+        };
+        assert_eq!(
+            err.to_string(),
+            alloc::format!(
+                "invalid length 50, expected a trimmed string of 1 to 10 bytes \
+                 (original value: \" {}\"...)",
+                "a".repeat(39),
+            ),
+        );
+
+        let err = Error::Untrimmed {
+            original: alloc::format!("{} ", "a".repeat(50)),
+            index: None,
+        };
+        assert_eq!(
+            err.to_string(),
+            alloc::format!(
+                "invalid value: string \"{}\"..., expected a string without leading or trailing \
+                 whitespace (found trailing whitespace \" \")",
+                "a".repeat(40),
+            ),
+        );
+    }
+
+    #[test]
+    fn constructors() {
+        #[derive(Debug, PartialEq, Eq)]
+        enum Kind {
+            Custom,
+            Length(usize),
+            Value(String),
+        }
+
+        impl de::Error for Kind {
+            fn custom<T: fmt::Display>(_msg: T) -> Self {
+                Self::Custom
+            }
+
+            fn invalid_value(unexp: de::Unexpected<'_>, _exp: &dyn de::Expected) -> Self {
+                Self::Value(unexp.to_string())
+            }
+
+            fn invalid_length(len: usize, _exp: &dyn de::Expected) -> Self {
+                Self::Length(len)
+            }
+        }
+
+        impl fmt::Display for Kind {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Debug::fmt(self, f)
+            }
+        }
+
+        impl de::StdError for Kind {}
+
+        let err = Error::Length {
+            original: " abc ".to_owned(),
+            len: 3,
+            min: 1,
+            max: 2,
+            unit: "bytes",
+        };
+        assert_eq!(err.into_de_error::<Kind>(), Kind::Length(3));
+
+        let err = Error::Untrimmed {
+            original: " a".to_owned(),
+            index: None,
+        };
+        assert_eq!(
+            err.into_de_error::<Kind>(),
+            Kind::Value(r#"string " a""#.to_owned()),
+        );
+
+        assert_eq!(Error::NoItems.into_de_error::<Kind>(), Kind::Length(0));
+    }
+
+    #[cfg(feature = "serde_path_to_error")]
+    #[test]
+    fn deserialize() {
+        use alloc::vec::Vec;
+
+        use serde::Deserialize;
+
+        #[derive(Debug, Deserialize)]
+        struct Foo {
+            #[serde(deserialize_with = "crate::seq::NonEmpty::<false>::vec_string")]
+            tags: Vec<String>,
+            #[serde(default, deserialize_with = "crate::parse")]
+            port: u16,
+            bar: Option<Bar>,
+        }
+
+        #[derive(Debug, Deserialize)]
+        #[serde(untagged)]
+        enum Bar {
+            Name(#[serde(deserialize_with = "crate::string_non_empty")] String),
+            Id(u64),
+        }
+
+        let mut de = serde_json::Deserializer::from_str(r#"{ "tags": ["a", " "] }"#);
+        let err = super::deserialize::<Foo, _>(&mut de).unwrap_err();
+        assert_eq!(err.path().to_string(), "tags[1]");
+        assert_eq!(
+            err.error(),
+            Some(&Error::Empty {
+                original: " ".to_owned(),
+                index: Some(1),
+            }),
+        );
+
+        let mut de = serde_json::Deserializer::from_str(r#"{ "tags": [], "port": " x " }"#);
+        let err = super::deserialize::<Foo, _>(&mut de).unwrap_err();
+        assert_eq!(err.path().to_string(), "port");
+        assert!(matches!(err.error(), Some(Error::Parse { ty: "u16", .. })));
+        assert_eq!(err.error().unwrap().original(), Some(" x "));
+
+        let mut de = serde_json::Deserializer::from_str(r#"{ "tags": [" a"], "bar": "b " }"#);
+        let foo = super::deserialize::<Foo, _>(&mut de).unwrap();
+        assert_eq!(foo.tags, ["a"]);
+        assert_eq!(foo.port, 0);
+        assert!(matches!(foo.bar, Some(Bar::Name(name)) if name == "b"));
+
+        // errors recovered from by untagged enums are not reported
+        let mut de = serde_json::Deserializer::from_str(r#"{ "tags": [], "bar": 5 }"#);
+        let foo = super::deserialize::<Foo, _>(&mut de).unwrap();
+        assert!(matches!(foo.bar, Some(Bar::Id(5))));
+
+        let mut de = serde_json::Deserializer::from_str(r#"{ "tags": [], "bar": " " }"#);
+        let err = super::deserialize::<Foo, _>(&mut de).unwrap_err();
+        assert_eq!(err.path().to_string(), "bar");
+        assert_eq!(err.error(), None);
+
+        // non-detrim errors
+        let mut de = serde_json::Deserializer::from_str(r#"{ "tags": [1] }"#);
+        let err = super::deserialize::<Foo, _>(&mut de).unwrap_err();
+        assert_eq!(err.path().to_string(), "tags[0]");
+        assert_eq!(err.error(), None);
+        assert!(err.into_inner().is_data());
+    }
+
+    #[cfg(feature = "serde_path_to_error")]
+    #[test]
+    fn deserialize_carried() {
+        use serde::{de::Error as _, Deserialize};
+
+        /// Recovers from a detrim error, then fails with an error of the same text.
+        #[derive(Debug)]
+        struct Impostor;
+
+        impl<'de> Deserialize<'de> for Impostor {
+            fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+                let val = String::deserialize(de)?;
+                let res = crate::string_non_empty(
+                    serde::de::value::StrDeserializer::<D::Error>::new(&val),
+                );
+                let msg = res.unwrap_err().to_string();
+                Err(D::Error::custom(msg))
+            }
+        }
+
+        #[derive(Debug, Deserialize)]
+        struct Foo {
+            #[serde(default, deserialize_with = "crate::parse")]
+            port: u16,
+            #[serde(default)]
+            impostor: Option<Impostor>,
+        }
+
+        let mut de = serde_json::Deserializer::from_str(r#"{ "port": " 80 " }"#);
+        let foo = super::deserialize::<Foo, _>(&mut de).unwrap();
+        assert_eq!(foo.port, 80);
+        assert!(foo.impostor.is_none());
+
+        let mut de = serde_json::Deserializer::from_str(r#"{ "impostor": " " }"#);
+        let err = super::deserialize::<Foo, _>(&mut de).unwrap_err();
+        assert_eq!(err.path().to_string(), "impostor");
+        assert!(err.to_string().contains(r#"expected non-empty string"#));
+        assert_eq!(err.error(), None);
+
+        // formats that rewrite messages, like by prefixing the variable name
+        let de = crate::env::Deserializer::from_vars("APP_", [("APP_PORT", " x ")]).unwrap();
+        let err = super::deserialize::<Foo, _>(de).unwrap_err();
+        assert!(err.inner().to_string().starts_with("APP_PORT: "));
+        assert!(matches!(err.error(), Some(Error::Parse { ty: "u16", .. })));
+
+        // errors raised on other threads do not leak into this one
+        let handle = std::thread::spawn(|| {
+            serde_json::from_str::<Foo>(r#"{ "port": "y" }"#).unwrap_err();
+        });
+        handle.join().unwrap();
+        let mut de = serde_json::Deserializer::from_str(r#"{ "port": true }"#);
+        let err = super::deserialize::<Foo, _>(&mut de).unwrap_err();
+        assert_eq!(err.error(), None);
+    }
+}
@@ -0,0 +1,110 @@
+use alloc::string::String;
+use core::hash::BuildHasher;
+
+use hashbrown::HashSet;
+use serde::Deserializer;
+
+use crate::seq::{EmptyItems, TrimSeqVisitor};
+
+/// Trims set of strings into a `hashbrown` `HashSet` during deserialization.
+///
+/// Strings are deduplicated _after_ being trimmed (i.e., differences in extraneous whitespace are
+/// handled). Does not require the `std` crate feature; the hasher `S` is inferred from the field
+/// type.
+pub fn hashbrown_hashset_string<'a, S, D>(de: D) -> Result<HashSet<String, S>, D::Error>
+where
+    S: BuildHasher + Default,
+    D: Deserializer<'a>,
+{
+    de.deserialize_seq(TrimSeqVisitor::new(EmptyItems::Keep, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString as _;
+    use core::hash::BuildHasherDefault;
+
+    use serde::Deserialize;
+
+    use super::*;
+
+    /// Trivial hasher, since `hashbrown`'s default hasher is not enabled.
+    #[derive(Default)]
+    struct FnvHasher(u64);
+
+    impl core::hash::Hasher for FnvHasher {
+        fn finish(&self) -> u64 {
+            self.0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for byte in bytes {
+                self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x100_0000_01b3);
+            }
+        }
+    }
+
+    type Set = HashSet<String, BuildHasherDefault<FnvHasher>>;
+
+    fn set(items: impl IntoIterator<Item = &'static str>) -> Set {
+        items.into_iter().map(Into::into).collect()
+    }
+
+    #[test]
+    fn hashbrown_hashset_string() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::hashbrown_hashset_string")]
+            foo: Set,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": 1 }"#).unwrap_err();
+
+        let foo = serde_json::from_str::<Foo>(r#"{ "foo": ["", " "] }"#).unwrap();
+        assert_eq!(foo.foo, set([""]));
+
+        let foo = serde_json::from_str::<Foo>(r#"{ "foo": ["  bar  ", "bar", " baz"] }"#).unwrap();
+        assert_eq!(foo.foo, set(["bar", "baz"]));
+    }
+
+    #[test]
+    fn hashbrown_hashset_string_empty_items() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "crate::seq::NonEmpty::<false>::hashbrown_hashset_string")]
+            foo: Set,
+            #[serde(deserialize_with = "crate::seq::SkipEmpty::<false>::hashbrown_hashset_string")]
+            bar: Set,
+        }
+
+        let err = serde_json::from_str::<Foo>(r#"{ "foo": ["bar", " "], "bar": [] }"#).unwrap_err();
+        assert!(err.to_string().contains("empty string at index 1"));
+
+        let foo = serde_json::from_str::<Foo>(r#"{ "foo": [" a"], "bar": [" ", "b "] }"#).unwrap();
+        assert_eq!(foo.foo, set(["a"]));
+        assert_eq!(foo.bar, set(["b"]));
+    }
+
+    #[test]
+    fn hashbrown_hashset_string_at_least_one() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "crate::seq::NonEmpty::<true>::hashbrown_hashset_string")]
+            foo: Set,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Bar {
+            #[serde(deserialize_with = "crate::seq::SkipEmpty::<true>::hashbrown_hashset_string")]
+            bar: Set,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": [] }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": [" "] }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": [" bar "] }"#).unwrap();
+
+        serde_json::from_str::<Bar>(r#"{ "bar": [] }"#).unwrap_err();
+        serde_json::from_str::<Bar>(r#"{ "bar": [" ", ""] }"#).unwrap_err();
+        serde_json::from_str::<Bar>(r#"{ "bar": [" ", " bar "] }"#).unwrap();
+    }
+}
@@ -1,24 +1,15 @@
-use std::{
-    borrow::ToOwned as _, collections::HashSet, iter::FromIterator as _, string::String, vec::Vec,
-};
+use std::{collections::HashSet, string::String};
 
-use serde::{Deserialize as _, Deserializer};
+use serde::Deserializer;
+
+use crate::seq::{EmptyItems, TrimSeqVisitor};
 
 /// Trims set of strings during deserialization.
 ///
 /// Strings are deduplicated _after_ being trimmed (i.e., differences in extraneous whitespace are
 /// handled).
 pub fn hashset_string<'a, D: Deserializer<'a>>(de: D) -> Result<HashSet<String>, D::Error> {
-    let mut set = Vec::<String>::deserialize(de)?;
-
-    for item in &mut set {
-        #[allow(clippy::assigning_clones)]
-        {
-            *item = item.trim().to_owned();
-        }
-    }
-
-    Ok(HashSet::from_iter(set))
+    de.deserialize_seq(TrimSeqVisitor::new(EmptyItems::Keep, false))
 }
 
 #[cfg(test)]
@@ -87,4 +78,85 @@ mod tests {
             serde_json::from_str(r#"{ "foo": ["  bar  ", "  bar"] }"#).unwrap(),
         );
     }
+
+    #[test]
+    fn hashset_string_non_empty() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "crate::seq::NonEmpty::<false>::hashset_string")]
+            foo: HashSet<String>,
+        }
+
+        impl Foo {
+            fn new(foo: impl IntoIterator<Item = impl Into<String>>) -> Self {
+                Self {
+                    foo: foo.into_iter().map(Into::into).collect(),
+                }
+            }
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": 1 }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": [""] }"#).unwrap_err();
+
+        let err = serde_json::from_str::<Foo>(r#"{ "foo": ["bar", "bar", " "] }"#).unwrap_err();
+        assert!(err.to_string().contains("empty string at index 2"));
+
+        assert_eq!(
+            Foo::new([""; 0]),
+            serde_json::from_str(r#"{ "foo": [] }"#).unwrap(),
+        );
+        assert_eq!(
+            Foo::new(["bar"]),
+            serde_json::from_str(r#"{ "foo": ["  bar  ", "bar"] }"#).unwrap(),
+        );
+    }
+
+    #[test]
+    fn hashset_string_skip_empty() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "crate::seq::SkipEmpty::<false>::hashset_string")]
+            foo: HashSet<String>,
+        }
+
+        impl Foo {
+            fn new(foo: impl IntoIterator<Item = impl Into<String>>) -> Self {
+                Self {
+                    foo: foo.into_iter().map(Into::into).collect(),
+                }
+            }
+        }
+
+        assert_eq!(
+            Foo::new([""; 0]),
+            serde_json::from_str(r#"{ "foo": ["", " "] }"#).unwrap(),
+        );
+        assert_eq!(
+            Foo::new(["bar"]),
+            serde_json::from_str(r#"{ "foo": [" bar", "  ", "bar "] }"#).unwrap(),
+        );
+    }
+
+    #[test]
+    fn hashset_string_at_least_one() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "crate::seq::NonEmpty::<true>::hashset_string")]
+            foo: HashSet<String>,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Bar {
+            #[serde(deserialize_with = "crate::seq::SkipEmpty::<true>::hashset_string")]
+            bar: HashSet<String>,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": [] }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": [" "] }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": [" bar "] }"#).unwrap();
+
+        serde_json::from_str::<Bar>(r#"{ "bar": [] }"#).unwrap_err();
+        serde_json::from_str::<Bar>(r#"{ "bar": [" ", ""] }"#).unwrap_err();
+        serde_json::from_str::<Bar>(r#"{ "bar": [" ", " bar "] }"#).unwrap();
+    }
 }
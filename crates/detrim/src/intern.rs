@@ -0,0 +1,359 @@
+//! Trimming deserializers that intern strings into shared `Arc<str>`s.
+//!
+//! Payloads that repeat the same values many times, like country codes or statuses, would
+//! otherwise allocate a separate string for each occurrence. Strings are trimmed before being
+//! interned, so `" US"` and `"US"` share an allocation.
+//!
+//! Interning is delegated to an [`Interner`]. A mutex-guarded [`StringInterner`] is provided
+//! behind the `std` crate feature; implement the trait to plug in another.
+//!
+//! Since `#[serde(deserialize_with)]` functions can not take arguments, the interner used by
+//! [`arc_str()`] and friends is selected by a [`GlobalInterner`] type parameter. When driving a
+//! deserializer manually, the [`ArcStrSeed`] and [`VecArcStrSeed`] seeds can be used with any
+//! interner instead.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "std")] {
+//! use std::sync::Arc;
+//!
+//! use detrim::intern::{GlobalInterner, StringInterner};
+//!
+//! static COUNTRIES: StringInterner = StringInterner::new();
+//!
+//! enum Countries {}
+//!
+//! impl GlobalInterner for Countries {
+//!     type Interner = StringInterner;
+//!
+//!     fn interner() -> &'static Self::Interner {
+//!         &COUNTRIES
+//!     }
+//! }
+//!
+//! #[derive(Debug, serde::Deserialize)]
+//! struct Order {
+//!     #[serde(deserialize_with = "detrim::intern::arc_str::<Countries, _>")]
+//!     country: Arc<str>,
+//! }
+//!
+//! let orders = serde_json::from_str::<Vec<Order>>(
+//!     r#"[{ "country": " US" }, { "country": "US" }]"#,
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(&*orders[0].country, "US");
+//! assert!(Arc::ptr_eq(&orders[0].country, &orders[1].country));
+//! assert_eq!(COUNTRIES.len(), 1);
+//! # }
+//! ```
+
+use alloc::{sync::Arc, vec::Vec};
+use core::{fmt, marker::PhantomData, str};
+#[cfg(feature = "std")]
+use std::{
+    collections::BTreeSet,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use serde::{de, Deserializer};
+
+use crate::{seq::VecSeedVisitor, string::trim};
+
+/// Types that can return a shared copy of a string.
+pub trait Interner {
+    /// Returns a shared string equal to `val`, allocating only if it has not been seen before.
+    fn intern(&self, val: &str) -> Arc<str>;
+}
+
+impl<I: Interner + ?Sized> Interner for &I {
+    fn intern(&self, val: &str) -> Arc<str> {
+        (**self).intern(val)
+    }
+}
+
+impl<I: Interner + ?Sized> Interner for Arc<I> {
+    fn intern(&self, val: &str) -> Arc<str> {
+        (**self).intern(val)
+    }
+}
+
+/// Selects the interner used by this module's `deserialize_with` functions.
+///
+/// See [module docs](self) for an example.
+pub trait GlobalInterner {
+    /// Type of interner.
+    type Interner: Interner + 'static;
+
+    /// Returns the interner.
+    fn interner() -> &'static Self::Interner;
+}
+
+/// Thread-safe interner backed by a mutex-guarded set.
+///
+/// Strings are never evicted, so the set grows with the number of distinct values seen. Can be
+/// used in a `static` since [`new()`](Self::new) is a `const fn`.
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+pub struct StringInterner {
+    set: Mutex<BTreeSet<Arc<str>>>,
+}
+
+#[cfg(feature = "std")]
+impl StringInterner {
+    /// Constructs an empty interner.
+    pub const fn new() -> Self {
+        Self {
+            set: Mutex::new(BTreeSet::new()),
+        }
+    }
+
+    /// Returns number of distinct strings interned.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns true if no strings have been interned.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Removes all interned strings.
+    ///
+    /// Strings already handed out are unaffected but will no longer be shared with new ones.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeSet<Arc<str>>> {
+        // the set is always left in a valid state, so poisoning can be ignored
+        self.set.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Interner for StringInterner {
+    fn intern(&self, val: &str) -> Arc<str> {
+        let mut set = self.lock();
+
+        if let Some(interned) = set.get(val) {
+            return Arc::clone(interned);
+        }
+
+        let interned = Arc::<str>::from(val);
+        set.insert(Arc::clone(&interned));
+        interned
+    }
+}
+
+/// Seed that trims a string and interns it using `I`.
+pub struct ArcStrSeed<'i, I: ?Sized> {
+    interner: &'i I,
+}
+
+impl<'i, I: Interner + ?Sized> ArcStrSeed<'i, I> {
+    /// Constructs a seed which interns into `interner`.
+    pub fn new(interner: &'i I) -> Self {
+        Self { interner }
+    }
+}
+
+impl<I: ?Sized> Clone for ArcStrSeed<'_, I> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I: ?Sized> Copy for ArcStrSeed<'_, I> {}
+
+impl<I: ?Sized> fmt::Debug for ArcStrSeed<'_, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArcStrSeed").finish_non_exhaustive()
+    }
+}
+
+impl<'de, I: Interner + ?Sized> de::Visitor<'de> for ArcStrSeed<'_, I> {
+    type Value = Arc<str>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a string")
+    }
+
+    fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+        Ok(self.interner.intern(trim(val)))
+    }
+
+    fn visit_bytes<E: de::Error>(self, val: &[u8]) -> Result<Self::Value, E> {
+        match str::from_utf8(val) {
+            Ok(val) => self.visit_str(val),
+            Err(_) => Err(de::Error::invalid_value(de::Unexpected::Bytes(val), &self)),
+        }
+    }
+}
+
+impl<'de, I: Interner + ?Sized> de::DeserializeSeed<'de> for ArcStrSeed<'_, I> {
+    type Value = Arc<str>;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_str(self)
+    }
+}
+
+/// Seed that trims a list of strings and interns each of them using `I`.
+pub struct VecArcStrSeed<'i, I: ?Sized> {
+    interner: &'i I,
+}
+
+impl<'i, I: Interner + ?Sized> VecArcStrSeed<'i, I> {
+    /// Constructs a seed which interns into `interner`.
+    pub fn new(interner: &'i I) -> Self {
+        Self { interner }
+    }
+}
+
+impl<I: ?Sized> fmt::Debug for VecArcStrSeed<'_, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VecArcStrSeed").finish_non_exhaustive()
+    }
+}
+
+impl<'de, I: Interner + ?Sized> de::DeserializeSeed<'de> for VecArcStrSeed<'_, I> {
+    type Value = Vec<Arc<str>>;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        let seed = ArcStrSeed::new(self.interner);
+        de.deserialize_seq(VecSeedVisitor::new("a sequence of strings", |_| seed))
+    }
+}
+
+/// Trims a string and interns it using `G`'s interner during deserialization.
+pub fn arc_str<'de, G, D>(de: D) -> Result<Arc<str>, D::Error>
+where
+    G: GlobalInterner,
+    D: Deserializer<'de>,
+{
+    de.deserialize_str(ArcStrSeed::new(G::interner()))
+}
+
+/// Trims an optional string and interns it using `G`'s interner during deserialization.
+pub fn option_arc_str<'de, G, D>(de: D) -> Result<Option<Arc<str>>, D::Error>
+where
+    G: GlobalInterner,
+    D: Deserializer<'de>,
+{
+    struct OptionArcStrVisitor<G>(PhantomData<fn() -> G>);
+
+    impl<'de, G: GlobalInterner> de::Visitor<'de> for OptionArcStrVisitor<G> {
+        type Value = Option<Arc<str>>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("an optional string")
+        }
+
+        fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+            arc_str::<G, D>(de).map(Some)
+        }
+    }
+
+    de.deserialize_option(OptionArcStrVisitor::<G>(PhantomData))
+}
+
+/// Trims list of strings and interns each of them using `G`'s interner during deserialization.
+pub fn vec_arc_str<'de, G, D>(de: D) -> Result<Vec<Arc<str>>, D::Error>
+where
+    G: GlobalInterner,
+    D: Deserializer<'de>,
+{
+    de::DeserializeSeed::deserialize(VecArcStrSeed::new(G::interner()), de)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use serde::{de::DeserializeSeed as _, Deserialize};
+
+    use super::*;
+
+    static INTERNER: StringInterner = StringInterner::new();
+
+    enum Test {}
+
+    impl GlobalInterner for Test {
+        type Interner = StringInterner;
+
+        fn interner() -> &'static Self::Interner {
+            &INTERNER
+        }
+    }
+
+    #[test]
+    fn string_interner() {
+        let interner = StringInterner::new();
+        assert!(interner.is_empty());
+
+        let a = interner.intern("foo");
+        let b = interner.intern("foo");
+        let c = interner.intern("bar");
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &c));
+        assert_eq!(interner.len(), 2);
+
+        interner.clear();
+        assert!(!Arc::ptr_eq(&a, &interner.intern("foo")));
+    }
+
+    #[test]
+    fn seeds() {
+        let interner = StringInterner::new();
+
+        let mut de = serde_json::Deserializer::from_str(r#"[" US", "US ", "DE", " b\\ar "]"#);
+        let list = VecArcStrSeed::new(&interner).deserialize(&mut de).unwrap();
+        assert_eq!(
+            list,
+            [Arc::from("US"), "US".into(), "DE".into(), "b\\ar".into()]
+        );
+        assert!(Arc::ptr_eq(&list[0], &list[1]));
+        assert_eq!(interner.len(), 3);
+
+        let mut de = serde_json::Deserializer::from_str(r#"" DE ""#);
+        let val = ArcStrSeed::new(&interner).deserialize(&mut de).unwrap();
+        assert!(Arc::ptr_eq(&val, &list[2]));
+
+        let mut de = serde_json::Deserializer::from_str("1");
+        ArcStrSeed::new(&interner).deserialize(&mut de).unwrap_err();
+    }
+
+    #[test]
+    fn global() {
+        #[derive(Debug, Deserialize)]
+        struct Foo {
+            #[serde(deserialize_with = "super::arc_str::<Test, _>")]
+            a: Arc<str>,
+            #[serde(deserialize_with = "super::option_arc_str::<Test, _>")]
+            b: Option<Arc<str>>,
+            #[serde(deserialize_with = "super::vec_arc_str::<Test, _>")]
+            c: Vec<Arc<str>>,
+        }
+
+        let foo = serde_json::from_str::<Foo>(
+            r#"{ "a": " global ", "b": "global", "c": ["global  ", "other"] }"#,
+        )
+        .unwrap();
+
+        assert_eq!(&*foo.a, "global");
+        assert!(Arc::ptr_eq(&foo.a, foo.b.as_ref().unwrap()));
+        assert!(Arc::ptr_eq(&foo.a, &foo.c[0]));
+        assert_eq!(&*foo.c[1], "other");
+
+        let foo = serde_json::from_str::<Foo>(r#"{ "a": "x", "b": null, "c": [] }"#).unwrap();
+        assert_eq!(foo.b, None);
+    }
+}
@@ -21,18 +21,73 @@
 
 extern crate alloc;
 
+pub mod bounded;
+mod btreeset_string;
+mod bytes;
+pub mod coerce;
 mod cow_str;
+pub mod env;
+pub mod error;
+#[cfg(feature = "hashbrown")]
+mod hashbrown_hashset_string;
 #[cfg(feature = "std")]
 mod hashset_string;
+pub mod intern;
+pub mod lossy;
+pub mod map;
+mod newtype;
+#[cfg(feature = "observe")]
+pub mod observe;
+mod option_non_empty;
+mod parse;
+#[cfg(feature = "std")]
+pub mod path;
+pub mod seq;
+#[cfg(feature = "serde_with")]
+pub mod serde_as;
+mod smart_str;
+pub mod split;
+pub mod strict;
 mod string;
 mod string_non_empty;
+mod trim;
+mod unique;
 mod vec_string;
+pub mod with;
+#[cfg(any(feature = "observe", feature = "serde_path_to_error"))]
+mod wrap;
+
+#[cfg(feature = "derive")]
+pub use detrim_macros::{detrim, Trim};
 
+#[cfg(feature = "bytes")]
+pub use crate::bytes::{option_shared_bytes, shared_bytes, vec_shared_bytes};
+#[cfg(feature = "hashbrown")]
+pub use crate::hashbrown_hashset_string::hashbrown_hashset_string;
 #[cfg(feature = "std")]
 pub use crate::hashset_string::hashset_string;
+#[cfg(feature = "compact_str")]
+pub use crate::smart_str::{compact_string, option_compact_string, vec_compact_string};
+#[cfg(feature = "smol_str")]
+pub use crate::smart_str::{option_smol_str, smol_str, vec_smol_str};
+#[cfg(feature = "std")]
+pub use crate::unique::hashset_string_unique;
+#[cfg(feature = "indexmap")]
+pub use crate::unique::indexset_string_unique;
 pub use crate::{
-    cow_str::cow_str,
-    string::{option_string, str, string},
+    btreeset_string::btreeset_string,
+    bytes::{byte_buf, bytes, option_byte_buf, option_bytes, vec_byte_buf},
+    cow_str::{cow_str, option_cow_str, vec_cow_str},
+    newtype::{NonEmptyString, TrimmedString},
+    option_non_empty::{option_non_empty, option_non_empty_parse},
+    parse::{option_parse, option_parse_or_native, parse, parse_or_native},
+    smart_str::{
+        arc_str, box_str, option_arc_str, option_box_str, option_rc_str, rc_str, vec_arc_str,
+        vec_box_str, vec_rc_str,
+    },
+    string::{option_str, option_string, str, string},
     string_non_empty::{option_string_non_empty, string_non_empty},
-    vec_string::vec_string,
+    trim::Trim,
+    unique::btreeset_string_unique,
+    vec_string::{vec_str, vec_string},
 };
@@ -0,0 +1,298 @@
+//! Deserializers that replace invalid UTF-8 instead of rejecting it.
+//!
+//! The trimming functions at the crate root return an error when given bytes that are not valid
+//! UTF-8. The functions in this module instead decode them with [`String::from_utf8_lossy()`]
+//! semantics, replacing each invalid sequence with `U+FFFD REPLACEMENT CHARACTER`, then trim the
+//! result. This is useful for binary formats whose producers send text in legacy encodings.
+//!
+//! Input that is already valid UTF-8 is handled exactly as by the crate root functions, including
+//! borrowing from the input where possible.
+//!
+//! Behind the `encoding_rs` crate feature, [`decode_cow_str()`] and [`decode_string()`] instead
+//! decode byte strings from a declared [`LegacyEncoding`], like [`Windows1252`].
+//!
+//! # Examples
+//!
+//! ```
+//! use serde::de::value::{BytesDeserializer, Error};
+//!
+//! // "café " encoded as Windows-1252
+//! let de = BytesDeserializer::<Error>::new(b"caf\xe9 ");
+//! assert_eq!(detrim::lossy::string(de).unwrap(), "caf\u{FFFD}");
+//! ```
+
+use alloc::{borrow::Cow, string::String, vec::Vec};
+use core::fmt;
+
+use serde::{de, Deserializer};
+
+use crate::string::{trim, trim_in_place};
+
+/// Trims an owned string, re-using its allocation.
+fn trim_owned(mut val: String) -> String {
+    trim_in_place(&mut val);
+    val
+}
+
+/// Replaces invalid UTF-8 in an owned byte string, re-using its allocation if it is valid.
+fn from_utf8_lossy_owned(val: Vec<u8>) -> String {
+    String::from_utf8(val)
+        .unwrap_or_else(|err| String::from_utf8_lossy(err.as_bytes()).into_owned())
+}
+
+/// Visitor that decodes byte strings using `decode` or `decode_owned` before trimming.
+///
+/// `decode` must only borrow when the input bytes are valid UTF-8 and need no conversion.
+struct LossyCowStrVisitor {
+    decode: fn(&[u8]) -> Cow<'_, str>,
+    decode_owned: fn(Vec<u8>) -> String,
+}
+
+impl<'a> de::Visitor<'a> for LossyCowStrVisitor {
+    type Value = Cow<'a, str>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a string or byte string")
+    }
+
+    fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(trim(val).into()))
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, val: &'a str) -> Result<Self::Value, E> {
+        Ok(Cow::Borrowed(trim(val)))
+    }
+
+    fn visit_string<E: de::Error>(self, val: String) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(trim_owned(val)))
+    }
+
+    fn visit_bytes<E: de::Error>(self, val: &[u8]) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(match (self.decode)(val) {
+            Cow::Borrowed(val) => trim(val).into(),
+            Cow::Owned(val) => trim_owned(val),
+        }))
+    }
+
+    fn visit_borrowed_bytes<E: de::Error>(self, val: &'a [u8]) -> Result<Self::Value, E> {
+        Ok(match (self.decode)(val) {
+            Cow::Borrowed(val) => Cow::Borrowed(trim(val)),
+            Cow::Owned(val) => Cow::Owned(trim_owned(val)),
+        })
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, val: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(trim_owned((self.decode_owned)(val))))
+    }
+}
+
+/// Trims a CoW string during deserialization, replacing invalid UTF-8.
+///
+/// Borrows from the input if it is valid UTF-8 and the deserializer supports borrowing.
+pub fn cow_str<'a, 'de: 'a, D: Deserializer<'de>>(de: D) -> Result<Cow<'a, str>, D::Error> {
+    de.deserialize_str(LossyCowStrVisitor {
+        decode: String::from_utf8_lossy,
+        decode_owned: from_utf8_lossy_owned,
+    })
+}
+
+/// Trims a string during deserialization, replacing invalid UTF-8.
+pub fn string<'de, D: Deserializer<'de>>(de: D) -> Result<String, D::Error> {
+    cow_str(de).map(Cow::into_owned)
+}
+
+/// Legacy encoding used to decode byte strings in [`decode_cow_str()`] and [`decode_string()`].
+#[cfg(feature = "encoding_rs")]
+pub trait LegacyEncoding {
+    /// Returns the encoding.
+    fn encoding() -> &'static encoding_rs::Encoding;
+}
+
+/// The Windows-1252 encoding, a superset of ISO-8859-1 (Latin-1) as decoded by web browsers.
+#[cfg(feature = "encoding_rs")]
+#[derive(Debug)]
+pub enum Windows1252 {}
+
+#[cfg(feature = "encoding_rs")]
+impl LegacyEncoding for Windows1252 {
+    fn encoding() -> &'static encoding_rs::Encoding {
+        encoding_rs::WINDOWS_1252
+    }
+}
+
+#[cfg(feature = "encoding_rs")]
+fn decode<E: LegacyEncoding>(val: &[u8]) -> Cow<'_, str> {
+    E::encoding().decode_without_bom_handling(val).0
+}
+
+/// Decodes an owned byte string, copying it if decoding does not change it.
+///
+/// This avoids checking the bytes again to re-use the allocation as a `String`.
+#[cfg(feature = "encoding_rs")]
+fn decode_owned<E: LegacyEncoding>(val: Vec<u8>) -> String {
+    decode::<E>(&val).into_owned()
+}
+
+/// Trims a CoW string during deserialization, decoding byte strings from the encoding `E`.
+///
+/// Strings are already decoded, so are only trimmed. Byte strings are decoded without BOM
+/// sniffing, replacing malformed sequences. Borrows from the input if decoding does not change it
+/// and the deserializer supports borrowing.
+///
+/// # Examples
+///
+/// ```
+/// use detrim::lossy::Windows1252;
+/// use serde::de::value::{BytesDeserializer, Error};
+///
+/// let de = BytesDeserializer::<Error>::new(b"caf\xe9 ");
+/// assert_eq!(detrim::lossy::decode_string::<Windows1252, _>(de).unwrap(), "café");
+/// ```
+#[cfg(feature = "encoding_rs")]
+pub fn decode_cow_str<'a, 'de: 'a, E, D>(de: D) -> Result<Cow<'a, str>, D::Error>
+where
+    E: LegacyEncoding,
+    D: Deserializer<'de>,
+{
+    de.deserialize_str(LossyCowStrVisitor {
+        decode: decode::<E>,
+        decode_owned: decode_owned::<E>,
+    })
+}
+
+/// Trims a string during deserialization, decoding byte strings from the encoding `E`.
+///
+/// See [`decode_cow_str()`] for details.
+#[cfg(feature = "encoding_rs")]
+pub fn decode_string<'de, E, D>(de: D) -> Result<String, D::Error>
+where
+    E: LegacyEncoding,
+    D: Deserializer<'de>,
+{
+    decode_cow_str::<E, D>(de).map(Cow::into_owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{
+        de::value::{BorrowedBytesDeserializer, BytesDeserializer, Error},
+        Deserialize,
+    };
+
+    use super::*;
+
+    #[test]
+    fn cow_str() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo<'a> {
+            #[serde(borrow, deserialize_with = "super::cow_str")]
+            foo: Cow<'a, str>,
+        }
+
+        serde_json::from_str::<Foo<'static>>(r#"{ "foo": 1 }"#).unwrap_err();
+
+        // borrowed when valid
+        let json = serde_json::from_str::<Foo<'_>>(r#"{ "foo": " bar " }"#).unwrap();
+        assert!(matches!(json.foo, Cow::Borrowed("bar")));
+
+        let val = super::cow_str(BorrowedBytesDeserializer::<Error>::new(b" bar\n")).unwrap();
+        assert!(matches!(val, Cow::Borrowed("bar")));
+
+        // owned when invalid sequences are replaced
+        let val = super::cow_str(BorrowedBytesDeserializer::<Error>::new(b"\tb\x80r ")).unwrap();
+        assert!(matches!(val, Cow::Owned(ref val) if val == "b\u{FFFD}r"));
+
+        let val = super::cow_str(BytesDeserializer::<Error>::new(b" \x93bar\x94 ")).unwrap();
+        assert_eq!(val, "\u{FFFD}bar\u{FFFD}");
+    }
+
+    #[test]
+    fn string() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::string")]
+            foo: String,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": null }"#).unwrap_err();
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": " b\\ar " }"#)
+                .unwrap()
+                .foo,
+            "b\\ar",
+        );
+
+        let val = super::string(BytesDeserializer::<Error>::new(b" caf\xe9 ")).unwrap();
+        assert_eq!(val, "caf\u{FFFD}");
+
+        // whitespace is trimmed after replacement
+        let val = super::string(BytesDeserializer::<Error>::new(b"\xa0bar ")).unwrap();
+        assert_eq!(val, "\u{FFFD}bar");
+    }
+
+    #[test]
+    fn byte_buf() {
+        use serde::de::Visitor as _;
+
+        let visitor = || LossyCowStrVisitor {
+            decode: String::from_utf8_lossy,
+            decode_owned: from_utf8_lossy_owned,
+        };
+
+        let val = visitor()
+            .visit_byte_buf::<Error>(b" bar\n".to_vec())
+            .unwrap();
+        assert_eq!(val, "bar");
+
+        let val = visitor()
+            .visit_byte_buf::<Error>(b"\tb\x80r ".to_vec())
+            .unwrap();
+        assert_eq!(val, "b\u{FFFD}r");
+
+        #[cfg(feature = "encoding_rs")]
+        {
+            let visitor = || LossyCowStrVisitor {
+                decode: super::decode::<Windows1252>,
+                decode_owned: decode_owned::<Windows1252>,
+            };
+
+            let val = visitor()
+                .visit_byte_buf::<Error>(b" bar\n".to_vec())
+                .unwrap();
+            assert_eq!(val, "bar");
+
+            let val = visitor()
+                .visit_byte_buf::<Error>(b" caf\xe9 ".to_vec())
+                .unwrap();
+            assert_eq!(val, "caf\u{E9}");
+        }
+    }
+
+    #[cfg(feature = "encoding_rs")]
+    #[test]
+    fn decode() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo<'a> {
+            #[serde(borrow, deserialize_with = "super::decode_cow_str::<Windows1252, _>")]
+            foo: Cow<'a, str>,
+        }
+
+        let json = serde_json::from_str::<Foo<'_>>(r#"{ "foo": " bar " }"#).unwrap();
+        assert!(matches!(json.foo, Cow::Borrowed("bar")));
+
+        let de = BorrowedBytesDeserializer::<Error>::new(b" bar\n");
+        let val = super::decode_cow_str::<Windows1252, _>(de).unwrap();
+        assert!(matches!(val, Cow::Borrowed("bar")));
+
+        // 0x93 and 0x94 are curly quotes, and 0xA0 is a non-breaking space
+        let de = BytesDeserializer::<Error>::new(b"\xa0\x93bar\x94 ");
+        let val = super::decode_string::<Windows1252, _>(de).unwrap();
+        assert_eq!(val, "\u{201C}bar\u{201D}");
+
+        // valid UTF-8 is still decoded as Windows-1252
+        let de = BytesDeserializer::<Error>::new("é".as_bytes());
+        let val = super::decode_string::<Windows1252, _>(de).unwrap();
+        assert_eq!(val, "\u{C3}\u{A9}");
+    }
+}
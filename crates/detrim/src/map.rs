@@ -0,0 +1,387 @@
+//! Trimming deserializers for maps with string keys.
+//!
+//! Keys are trimmed by the functions on the collision policy types: [`Reject`], [`KeepFirst`], and
+//! [`KeepLast`]. The policy decides what happens when two keys are equal after trimming. Values
+//! alone are trimmed by [`map_values()`].
+//!
+//! Values are trimmed using their [`Trim`] implementation.
+//!
+//! Each function works with both `BTreeMap<String, V>` and `HashMap<String, V>` (the latter behind
+//! the `std` crate feature).
+//!
+//! # Examples
+//!
+//! ```
+//! use std::collections::BTreeMap;
+//!
+//! #[derive(Debug, serde::Deserialize)]
+//! struct Resource {
+//!     #[serde(deserialize_with = "detrim::map::Reject::map")]
+//!     labels: BTreeMap<String, Vec<String>>,
+//! }
+//!
+//! let res = serde_json::from_str::<Resource>(r#"{ "labels": { " env ": [" prod "] } }"#)
+//!     .unwrap();
+//! assert_eq!(res.labels["env"], ["prod"]);
+//!
+//! let err = serde_json::from_str::<Resource>(r#"{ "labels": { "env": [], " env": [] } }"#)
+//!     .unwrap_err();
+//! assert!(err.to_string().contains(r#""env" and " env""#));
+//! ```
+
+use alloc::{collections::BTreeMap, string::String};
+use core::{fmt, marker::PhantomData};
+#[cfg(feature = "std")]
+use std::{collections::HashMap, hash::BuildHasher};
+
+use serde::{de, Deserialize, Deserializer};
+
+use crate::{error::Error, seq, string::trim_in_place, Trim};
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Map with string keys that detrim can deserialize into.
+///
+/// This trait is sealed and implemented for `BTreeMap<String, V>` and `HashMap<String, V, S>`.
+pub trait StringMap: private::Sealed {
+    /// Value type of the map.
+    type Value;
+
+    #[doc(hidden)]
+    fn with_capacity(capacity: usize) -> Self;
+
+    #[doc(hidden)]
+    fn contains_key(&self, key: &str) -> bool;
+
+    #[doc(hidden)]
+    fn insert(&mut self, key: String, val: Self::Value);
+}
+
+impl<V> private::Sealed for BTreeMap<String, V> {}
+
+impl<V> StringMap for BTreeMap<String, V> {
+    type Value = V;
+
+    fn with_capacity(_capacity: usize) -> Self {
+        BTreeMap::new()
+    }
+
+    fn contains_key(&self, key: &str) -> bool {
+        BTreeMap::contains_key(self, key)
+    }
+
+    fn insert(&mut self, key: String, val: Self::Value) {
+        BTreeMap::insert(self, key, val);
+    }
+}
+
+#[cfg(feature = "std")]
+impl<V, S> private::Sealed for HashMap<String, V, S> {}
+
+#[cfg(feature = "std")]
+impl<V, S: BuildHasher + Default> StringMap for HashMap<String, V, S> {
+    type Value = V;
+
+    fn with_capacity(capacity: usize) -> Self {
+        HashMap::with_capacity_and_hasher(capacity, S::default())
+    }
+
+    fn contains_key(&self, key: &str) -> bool {
+        HashMap::contains_key(self, key)
+    }
+
+    fn insert(&mut self, key: String, val: Self::Value) {
+        HashMap::insert(self, key, val);
+    }
+}
+
+/// Behavior when two keys are equal after trimming.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Collision {
+    Reject,
+    KeepFirst,
+    KeepLast,
+}
+
+/// Visitor that trims keys and/or values of a map, applying a collision policy to trimmed keys.
+struct TrimMapVisitor<M: StringMap> {
+    collision: Collision,
+    trim_keys: bool,
+    trim_value: Option<fn(&mut M::Value)>,
+    map: PhantomData<fn() -> M>,
+}
+
+impl<M: StringMap> TrimMapVisitor<M> {
+    fn new(collision: Collision, trim_keys: bool, trim_value: Option<fn(&mut M::Value)>) -> Self {
+        Self {
+            collision,
+            trim_keys,
+            trim_value,
+            map: PhantomData,
+        }
+    }
+}
+
+/// Seed that deserializes a map value, then trims it.
+///
+/// Trimming happens inside the value's deserialization, rather than after it is returned, so that
+/// observers see the value's path.
+struct TrimValueSeed<V> {
+    trim_value: Option<fn(&mut V)>,
+    value: PhantomData<fn() -> V>,
+}
+
+impl<'de, V: Deserialize<'de>> de::DeserializeSeed<'de> for TrimValueSeed<V> {
+    type Value = V;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        let mut val = V::deserialize(de)?;
+
+        if let Some(trim_value) = self.trim_value {
+            trim_value(&mut val);
+        }
+
+        Ok(val)
+    }
+}
+
+impl<'de, M> de::Visitor<'de> for TrimMapVisitor<M>
+where
+    M: StringMap,
+    M::Value: Deserialize<'de>,
+{
+    type Value = M;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a map with string keys")
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut map = M::with_capacity(seq::capacity(access.size_hint()));
+
+        // original spellings of trimmed keys, only tracked when collisions are rejected
+        let mut originals = BTreeMap::<String, String>::new();
+
+        while let Some(mut key) = access.next_key::<String>()? {
+            let val = access.next_value_seed(TrimValueSeed {
+                trim_value: self.trim_value,
+                value: PhantomData,
+            })?;
+
+            if self.trim_keys {
+                let original = (self.collision == Collision::Reject).then(|| key.clone());
+                trim_in_place(&mut key);
+
+                if map.contains_key(&key) {
+                    match self.collision {
+                        Collision::KeepFirst => continue,
+                        Collision::KeepLast => {}
+                        Collision::Reject => {
+                            return Err(Error::DuplicateKey {
+                                first: originals[&key].clone(),
+                                second: original.unwrap_or_default(),
+                            }
+                            .into_de_error());
+                        }
+                    }
+                }
+
+                if let Some(original) = original {
+                    originals.insert(key.clone(), original);
+                }
+            }
+
+            map.insert(key, val);
+        }
+
+        Ok(map)
+    }
+}
+
+/// Trims values of a map during deserialization.
+///
+/// Keys are not modified. See [module docs](self) for more.
+pub fn map_values<'de, M, D>(de: D) -> Result<M, D::Error>
+where
+    M: StringMap,
+    M::Value: Deserialize<'de> + Trim,
+    D: Deserializer<'de>,
+{
+    de.deserialize_map(TrimMapVisitor::new(
+        Collision::KeepLast,
+        false,
+        Some(Trim::trim_in_place),
+    ))
+}
+
+macro_rules! impl_collision_policy {
+    ($ty:ident, $collision:ident, $doc:literal) => {
+        #[doc = $doc]
+        ///
+        /// See [module docs](self) for more.
+        #[derive(Debug)]
+        pub enum $ty {}
+
+        impl $ty {
+            /// Trims keys of a map during deserialization.
+            ///
+            /// Values are deserialized unchanged.
+            pub fn map_keys<'de, M, D>(de: D) -> Result<M, D::Error>
+            where
+                M: StringMap,
+                M::Value: Deserialize<'de>,
+                D: Deserializer<'de>,
+            {
+                de.deserialize_map(TrimMapVisitor::new(Collision::$collision, true, None))
+            }
+
+            /// Trims keys and values of a map during deserialization.
+            pub fn map<'de, M, D>(de: D) -> Result<M, D::Error>
+            where
+                M: StringMap,
+                M::Value: Deserialize<'de> + Trim,
+                D: Deserializer<'de>,
+            {
+                de.deserialize_map(TrimMapVisitor::new(
+                    Collision::$collision,
+                    true,
+                    Some(Trim::trim_in_place),
+                ))
+            }
+        }
+    };
+}
+
+impl_collision_policy!(
+    Reject,
+    Reject,
+    "Returns an error naming both original keys when two keys are equal after trimming."
+);
+impl_collision_policy!(
+    KeepFirst,
+    KeepFirst,
+    "Keeps the first entry when two keys are equal after trimming."
+);
+impl_collision_policy!(
+    KeepLast,
+    KeepLast,
+    "Keeps the last entry when two keys are equal after trimming."
+);
+
+#[cfg(test)]
+mod tests {
+    use alloc::{borrow::ToOwned as _, string::ToString as _, vec, vec::Vec};
+
+    use serde::Deserialize;
+
+    use super::*;
+
+    fn btreemap<V>(entries: impl IntoIterator<Item = (&'static str, V)>) -> BTreeMap<String, V> {
+        entries
+            .into_iter()
+            .map(|(key, val)| (key.to_owned(), val))
+            .collect()
+    }
+
+    #[test]
+    fn map_values() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::map_values")]
+            foo: BTreeMap<String, String>,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": [] }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": { "a": 1 } }"#).unwrap_err();
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": {} }"#).unwrap().foo,
+            btreemap::<String>([]),
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": { " a ": " bar ", "b": "" } }"#)
+                .unwrap()
+                .foo,
+            btreemap([(" a ", "bar".to_owned()), ("b", "".to_owned())]),
+        );
+    }
+
+    #[test]
+    fn reject() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::Reject::map_keys")]
+            foo: BTreeMap<String, String>,
+        }
+
+        let err =
+            serde_json::from_str::<Foo>(r#"{ "foo": { "a": "1", " a ": "2" } }"#).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with(r#"invalid value: keys "a" and " a " are equal after trimming"#));
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": { " a ": " 1 ", "b": "2" } }"#)
+                .unwrap()
+                .foo,
+            btreemap([("a", " 1 ".to_owned()), ("b", "2".to_owned())]),
+        );
+    }
+
+    #[test]
+    fn keep_first() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::KeepFirst::map")]
+            foo: BTreeMap<String, Option<String>>,
+        }
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": { "a": " 1 ", " a ": "2", "b": null } }"#)
+                .unwrap()
+                .foo,
+            btreemap([("a", Some("1".to_owned())), ("b", None)]),
+        );
+    }
+
+    #[test]
+    fn keep_last() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::KeepLast::map")]
+            foo: BTreeMap<String, Vec<String>>,
+        }
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": { "a": [" 1 "], " a ": [" 2", "3 "] } }"#)
+                .unwrap()
+                .foo,
+            btreemap([("a", vec!["2".to_owned(), "3".to_owned()])]),
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hashmap() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::Reject::map")]
+            foo: HashMap<String, String>,
+            #[serde(deserialize_with = "super::map_values")]
+            bar: HashMap<String, String>,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": { "a": "", "a ": "" }, "bar": {} }"#).unwrap_err();
+
+        let json =
+            serde_json::from_str::<Foo>(r#"{ "foo": { " a ": " 1 " }, "bar": { " b ": " 2 " } }"#)
+                .unwrap();
+        assert_eq!(json.foo, HashMap::from([("a".to_owned(), "1".to_owned())]));
+        assert_eq!(
+            json.bar,
+            HashMap::from([(" b ".to_owned(), "2".to_owned())]),
+        );
+    }
+}
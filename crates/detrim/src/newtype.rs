@@ -0,0 +1,288 @@
+use alloc::{borrow::ToOwned as _, string::String};
+use core::{borrow::Borrow, convert::Infallible, fmt, ops::Deref, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    error::Error,
+    string::{is_trim_whitespace, trim, trim_in_place},
+};
+
+/// Returns the error for a value that is empty after trimming.
+fn empty(original: String) -> Error {
+    Error::Empty {
+        original,
+        index: None,
+    }
+}
+
+/// Implements traits shared by the string newtypes.
+macro_rules! impl_string_newtype {
+    ($ty:ident) => {
+        impl $ty {
+            /// Returns the string as a slice.
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            /// Converts into the inner string.
+            pub fn into_string(self) -> String {
+                self.0
+            }
+        }
+
+        impl Deref for $ty {
+            type Target = str;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $ty {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl Borrow<str> for $ty {
+            fn borrow(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl PartialEq<str> for $ty {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $ty {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl From<$ty> for String {
+            fn from(val: $ty) -> Self {
+                val.0
+            }
+        }
+
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+                ser.serialize_str(&self.0)
+            }
+        }
+    };
+}
+
+/// String with no leading or trailing whitespace.
+///
+/// Can only be constructed by trimming, via [`Deserialize`], [`From<String>`], or [`FromStr`].
+///
+/// # Examples
+///
+/// ```
+/// use detrim::TrimmedString;
+///
+/// #[derive(Debug, serde::Deserialize)]
+/// struct Form {
+///     name: TrimmedString,
+/// }
+///
+/// let form = serde_json::from_str::<Form>(r#"{ "name": "  ferris   " }"#).unwrap();
+/// assert_eq!(form.name, "ferris");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TrimmedString(String);
+
+impl_string_newtype!(TrimmedString);
+
+impl From<String> for TrimmedString {
+    fn from(mut val: String) -> Self {
+        trim_in_place(&mut val);
+        Self(val)
+    }
+}
+
+impl From<&str> for TrimmedString {
+    fn from(val: &str) -> Self {
+        Self(trim(val).to_owned())
+    }
+}
+
+impl From<NonEmptyString> for TrimmedString {
+    fn from(val: NonEmptyString) -> Self {
+        Self(val.0)
+    }
+}
+
+impl FromStr for TrimmedString {
+    type Err = Infallible;
+
+    fn from_str(val: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(val))
+    }
+}
+
+impl<'de> Deserialize<'de> for TrimmedString {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        crate::string(de).map(Self)
+    }
+}
+
+/// String with no leading or trailing whitespace that is guaranteed to be non-empty.
+///
+/// Can only be constructed by trimming and validating, via [`Deserialize`], [`TryFrom<String>`],
+/// or [`FromStr`]. Blank strings are rejected with [`Error::Empty`].
+///
+/// # Examples
+///
+/// ```
+/// use detrim::NonEmptyString;
+///
+/// #[derive(Debug, serde::Deserialize)]
+/// struct Form {
+///     name: NonEmptyString,
+/// }
+///
+/// let form = serde_json::from_str::<Form>(r#"{ "name": "  ferris   " }"#).unwrap();
+/// assert_eq!(form.name, "ferris");
+///
+/// serde_json::from_str::<Form>(r#"{ "name": "  " }"#).unwrap_err();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NonEmptyString(String);
+
+impl_string_newtype!(NonEmptyString);
+
+impl TryFrom<String> for NonEmptyString {
+    type Error = Error;
+
+    fn try_from(mut val: String) -> Result<Self, Self::Error> {
+        if val.chars().all(is_trim_whitespace) {
+            return Err(empty(val));
+        }
+
+        trim_in_place(&mut val);
+        Ok(Self(val))
+    }
+}
+
+impl TryFrom<&str> for NonEmptyString {
+    type Error = Error;
+
+    fn try_from(val: &str) -> Result<Self, Self::Error> {
+        match trim(val) {
+            "" => Err(empty(val.to_owned())),
+            trimmed => Ok(Self(trimmed.to_owned())),
+        }
+    }
+}
+
+impl TryFrom<TrimmedString> for NonEmptyString {
+    type Error = Error;
+
+    fn try_from(val: TrimmedString) -> Result<Self, Self::Error> {
+        if val.0.is_empty() {
+            return Err(empty(val.0));
+        }
+
+        Ok(Self(val.0))
+    }
+}
+
+impl FromStr for NonEmptyString {
+    type Err = Error;
+
+    fn from_str(val: &str) -> Result<Self, Self::Err> {
+        Self::try_from(val)
+    }
+}
+
+impl<'de> Deserialize<'de> for NonEmptyString {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        crate::string_non_empty(de).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{collections::BTreeSet, string::ToString as _};
+
+    use super::*;
+
+    #[test]
+    fn trimmed_string() {
+        assert_eq!(TrimmedString::from(" foo "), "foo");
+        assert_eq!(TrimmedString::from(String::from("\tfoo bar\n")), "foo bar");
+        assert_eq!(" ".parse::<TrimmedString>().unwrap(), "");
+        assert_eq!(TrimmedString::from(" foo ").to_string(), "foo");
+        assert_eq!(TrimmedString::from(" foo ").len(), 3);
+
+        assert_eq!(
+            serde_json::from_str::<TrimmedString>(r#"" foo ""#).unwrap(),
+            "foo",
+        );
+        assert_eq!(
+            serde_json::to_string(&TrimmedString::from(" foo ")).unwrap(),
+            r#""foo""#,
+        );
+        serde_json::from_str::<TrimmedString>("1").unwrap_err();
+    }
+
+    #[test]
+    fn non_empty_string() {
+        assert_eq!(NonEmptyString::try_from(""), Err(empty(String::new())));
+        assert_eq!(
+            NonEmptyString::try_from(String::from(" \n")),
+            Err(empty(String::from(" \n"))),
+        );
+        assert_eq!(
+            NonEmptyString::try_from(TrimmedString::from(" ")),
+            Err(empty(String::new())),
+        );
+        assert_eq!(
+            " ".parse::<NonEmptyString>().unwrap_err().to_string(),
+            r#"invalid value: string " ", expected non-empty string"#,
+        );
+        assert_eq!(NonEmptyString::try_from(" foo ").unwrap(), "foo");
+        assert_eq!(" foo ".parse::<NonEmptyString>().unwrap().as_str(), "foo");
+        assert_eq!(
+            NonEmptyString::try_from(String::from(" foo "))
+                .unwrap()
+                .into_string(),
+            "foo",
+        );
+
+        serde_json::from_str::<NonEmptyString>(r#""""#).unwrap_err();
+        serde_json::from_str::<NonEmptyString>(r#"" ""#).unwrap_err();
+        assert_eq!(
+            serde_json::from_str::<NonEmptyString>(r#"" foo ""#).unwrap(),
+            "foo",
+        );
+        assert_eq!(
+            serde_json::to_string(&NonEmptyString::try_from("foo").unwrap()).unwrap(),
+            r#""foo""#,
+        );
+    }
+
+    #[test]
+    fn borrow() {
+        let set = ["b ", " a"]
+            .into_iter()
+            .map(|val| NonEmptyString::try_from(val).unwrap())
+            .collect::<BTreeSet<_>>();
+
+        assert!(set.contains("a"));
+        assert!(set.contains("b"));
+        assert!(!set.contains(" a"));
+    }
+}
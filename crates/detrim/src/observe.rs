@@ -0,0 +1,413 @@
+//! Hooks for observing values altered by trimming.
+//!
+//! Wrapping code in a [`scope()`] installs a hook on the current thread which is called with a
+//! [`TrimEvent`] each time one of this crate's deserializers (or a [`Trim`](crate::Trim)
+//! implementation) removes whitespace from a string. Values that were already trimmed are not
+//! reported. This is useful for measuring how often clients send untrimmed data before deciding to
+//! reject it using the [`strict`](crate::strict) module.
+//!
+//! Field paths are not known to the deserializers themselves. To include them in events,
+//! deserialize through [`deserialize()`], which tracks the map keys, sequence indices, and enum
+//! variants leading to each value.
+//!
+//! Requires the `observe` crate feature. When no scope is active, the only overhead is a single
+//! relaxed atomic load per trimmed value.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::{cell::RefCell, rc::Rc};
+//!
+//! #[derive(Debug, serde::Deserialize)]
+//! struct Form {
+//!     #[serde(deserialize_with = "detrim::string")]
+//!     name: String,
+//!     #[serde(deserialize_with = "detrim::vec_string")]
+//!     tags: Vec<String>,
+//! }
+//!
+//! let paths = Rc::new(RefCell::new(Vec::new()));
+//!
+//! let form = detrim::observe::scope(
+//!     {
+//!         let paths = Rc::clone(&paths);
+//!         move |event| paths.borrow_mut().push(event.path().unwrap().to_string())
+//!     },
+//!     || {
+//!         let json = r#"{ "name": " ferris", "tags": ["crab", "rust  "] }"#;
+//!         let mut de = serde_json::Deserializer::from_str(json);
+//!         detrim::observe::deserialize::<Form, _>(&mut de)
+//!     },
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(form.name, "ferris");
+//! assert_eq!(*paths.borrow(), ["name", "tags[1]"]);
+//! ```
+//!
+//! # Path Tracking
+//!
+//! Path tracking is best-effort. Content that serde buffers before deserializing, such as untagged
+//! or internally tagged enums and flattened fields, is reported with the path of the outermost
+//! buffered value.
+
+use std::{
+    boxed::Box,
+    cell::RefCell,
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+    vec::Vec,
+};
+
+use serde::{de, Deserialize, Deserializer};
+
+pub use crate::wrap::Segment;
+use crate::wrap::{Layer, Wrap};
+
+/// Number of scopes active across all threads.
+///
+/// Allows skipping thread-local lookups entirely when no hook is installed anywhere.
+static ACTIVE_SCOPES: AtomicUsize = AtomicUsize::new(0);
+
+type Hook = Box<dyn FnMut(&TrimEvent<'_>)>;
+
+std::thread_local! {
+    static HOOK: RefCell<Option<Hook>> = const { RefCell::new(None) };
+    static PATH: RefCell<Option<Vec<Segment>>> = const { RefCell::new(None) };
+}
+
+/// A value that was altered by trimming.
+#[derive(Debug)]
+pub struct TrimEvent<'a> {
+    path: Option<&'a [Segment]>,
+    removed_prefix_len: usize,
+    removed_suffix_len: usize,
+}
+
+impl TrimEvent<'_> {
+    /// Returns path to the trimmed value, if deserializing through [`deserialize()`].
+    pub fn path(&self) -> Option<Path<'_>> {
+        self.path.map(Path)
+    }
+
+    /// Returns number of bytes of leading whitespace removed.
+    pub fn removed_prefix_len(&self) -> usize {
+        self.removed_prefix_len
+    }
+
+    /// Returns number of bytes of trailing whitespace removed.
+    pub fn removed_suffix_len(&self) -> usize {
+        self.removed_suffix_len
+    }
+}
+
+/// Path to a value within the deserialized input.
+///
+/// Displays as dotted field names and bracketed indices, e.g., `users[0].name`. The root path
+/// displays as `.`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Path<'a>(&'a [Segment]);
+
+impl<'a> Path<'a> {
+    /// Returns the segments of this path, outermost first.
+    pub fn segments(&self) -> &'a [Segment] {
+        self.0
+    }
+}
+
+impl fmt::Display for Path<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return f.write_str(".");
+        }
+
+        for (idx, segment) in self.0.iter().enumerate() {
+            match segment {
+                Segment::Index(index) => write!(f, "[{index}]")?,
+                Segment::Field(name) if idx == 0 => f.write_str(name)?,
+                Segment::Field(name) => write!(f, ".{name}")?,
+                Segment::Unknown if idx == 0 => f.write_str("?")?,
+                Segment::Unknown => f.write_str(".?")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Calls `f` with `hook` installed for the current thread, returning its result.
+///
+/// Scopes can be nested, in which case only the innermost hook is called. The hook is not called
+/// for trimming that happens inside itself.
+pub fn scope<R>(hook: impl FnMut(&TrimEvent<'_>) + 'static, f: impl FnOnce() -> R) -> R {
+    /// Restores previous hook, even if `f` panics.
+    struct Guard(Option<Hook>);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            let prev = self.0.take();
+            HOOK.with(|hook| *hook.borrow_mut() = prev);
+            ACTIVE_SCOPES.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    ACTIVE_SCOPES.fetch_add(1, Ordering::Relaxed);
+    let prev = HOOK.with(|cell| cell.borrow_mut().replace(Box::new(hook)));
+    let _guard = Guard(prev);
+
+    f()
+}
+
+/// Deserializes a value while tracking the path to each trimmed string.
+///
+/// Paths are only tracked inside an active [`scope()`]. Otherwise, this is equivalent to calling
+/// [`T::deserialize()`](Deserialize::deserialize).
+pub fn deserialize<'de, T, D>(de: D) -> Result<T, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    /// Restores previous path, so that nested calls do not clobber outer paths.
+    struct Guard(Option<Vec<Segment>>);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            let prev = self.0.take();
+            PATH.with(|path| *path.borrow_mut() = prev);
+        }
+    }
+
+    if !is_active() {
+        return T::deserialize(de);
+    }
+
+    let prev = PATH.with(|path| path.borrow_mut().replace(Vec::new()));
+    let _guard = Guard(prev);
+
+    T::deserialize(Wrap::<_, Track>::new(de))
+}
+
+fn is_active() -> bool {
+    ACTIVE_SCOPES.load(Ordering::Relaxed) != 0
+}
+
+/// Reports that `trimmed` was produced by trimming `original`.
+pub(crate) fn report_trimmed(original: &str, trimmed: &str) {
+    if original.len() == trimmed.len() {
+        return;
+    }
+
+    let prefix_len = trimmed.as_ptr() as usize - original.as_ptr() as usize;
+    report(prefix_len, original.len() - trimmed.len() - prefix_len);
+}
+
+/// Reports that a value had whitespace removed from either end.
+pub(crate) fn report(removed_prefix_len: usize, removed_suffix_len: usize) {
+    if (removed_prefix_len == 0 && removed_suffix_len == 0) || !is_active() {
+        return;
+    }
+
+    // both are taken out while the hook runs, so that it can trim and deserialize without
+    // re-entering itself
+    let Some(mut hook) = HOOK.with(|hook| hook.borrow_mut().take()) else {
+        return;
+    };
+    let path = PATH.with(|path| path.borrow_mut().take());
+
+    hook(&TrimEvent {
+        path: path.as_deref(),
+        removed_prefix_len,
+        removed_suffix_len,
+    });
+
+    PATH.with(|cell| *cell.borrow_mut() = path);
+    HOOK.with(|cell| *cell.borrow_mut() = Some(hook));
+}
+
+/// Wrapper layer which records the path to each value.
+struct Track;
+
+impl Layer for Track {
+    type Error<E: de::Error> = E;
+
+    const TRACK_PATHS: bool = true;
+
+    fn lift<T, E: de::Error>(res: Result<T, E>) -> Result<T, E> {
+        res
+    }
+
+    fn lower<E: de::Error>(err: E) -> E {
+        err
+    }
+
+    /// Calls `f` with `segment` appended to the current path.
+    fn with_segment<R>(segment: Segment, f: impl FnOnce() -> R) -> R {
+        struct Guard;
+
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                PATH.with(|path| {
+                    if let Some(path) = path.borrow_mut().as_mut() {
+                        path.pop();
+                    }
+                });
+            }
+        }
+
+        PATH.with(|path| {
+            if let Some(path) = path.borrow_mut().as_mut() {
+                path.push(segment);
+            }
+        });
+        let _guard = Guard;
+
+        f()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::ToOwned as _, collections::BTreeMap, rc::Rc};
+
+    use super::*;
+
+    /// Deserializes `json` within a scope, returning the value and the recorded events.
+    fn observe<T: de::DeserializeOwned>(
+        json: &str,
+        track_paths: bool,
+    ) -> (T, Vec<(Option<String>, usize, usize)>) {
+        let events = Rc::new(RefCell::new(Vec::new()));
+
+        let val = scope(
+            {
+                let events = Rc::clone(&events);
+                move |event| {
+                    events.borrow_mut().push((
+                        event.path().map(|path| path.to_string()),
+                        event.removed_prefix_len(),
+                        event.removed_suffix_len(),
+                    ));
+                }
+            },
+            || {
+                let mut de = serde_json::Deserializer::from_str(json);
+
+                if track_paths {
+                    deserialize(&mut de).unwrap()
+                } else {
+                    T::deserialize(&mut de).unwrap()
+                }
+            },
+        );
+
+        let events = events.borrow().clone();
+        (val, events)
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Foo {
+        #[serde(deserialize_with = "crate::string")]
+        a: String,
+        #[serde(default, deserialize_with = "crate::option_string")]
+        b: Option<String>,
+        #[serde(default, deserialize_with = "crate::vec_string")]
+        c: Vec<String>,
+        #[serde(default)]
+        d: BTreeMap<String, Bar>,
+        #[serde(default)]
+        e: Option<Baz>,
+        #[serde(default, deserialize_with = "crate::map::map_values")]
+        f: BTreeMap<String, Vec<String>>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Bar(#[serde(deserialize_with = "crate::string")] String);
+
+    #[derive(Debug, Deserialize)]
+    enum Baz {
+        Qux {
+            #[serde(deserialize_with = "crate::string")]
+            quux: String,
+        },
+    }
+
+    #[test]
+    fn events() {
+        let (foo, events) = observe::<Foo>(r#"{ "a": " x  ", "b": "y", "c": ["z\n"] }"#, false);
+        assert_eq!(foo.a, "x");
+        assert_eq!(foo.b.as_deref(), Some("y"));
+        assert_eq!(foo.c, ["z"]);
+        assert_eq!(events, [(None, 1, 2), (None, 0, 1)]);
+
+        let (_, events) = observe::<Foo>(r#"{ "a": "x", "b": null }"#, false);
+        assert_eq!(events, []);
+    }
+
+    #[test]
+    fn paths() {
+        let json = r#"{
+            "a": "\tx",
+            "b": " y",
+            "c": ["z", "z "],
+            "d": { "k": "  v" },
+            "e": { "Qux": { "quux": " w " } },
+            "f": { "k": ["u", "u "] }
+        }"#;
+
+        let (foo, events) = observe::<Foo>(json, true);
+        assert_eq!(foo.d["k"].0, "v");
+        assert_eq!(foo.f["k"], ["u", "u"]);
+        assert!(matches!(foo.e, Some(Baz::Qux { quux }) if quux == "w"));
+        assert_eq!(
+            events,
+            [
+                (Some("a".to_owned()), 1, 0),
+                (Some("b".to_owned()), 1, 0),
+                (Some("c[1]".to_owned()), 0, 1),
+                (Some("d.k".to_owned()), 2, 0),
+                (Some("e.Qux.quux".to_owned()), 1, 1),
+                (Some("f.k".to_owned()), 0, 1),
+            ],
+        );
+
+        let (_, events) = observe::<Bar>(r#"" x""#, true);
+        assert_eq!(events, [(Some(".".to_owned()), 1, 0)]);
+    }
+
+    #[test]
+    fn nested_scopes() {
+        let outer = Rc::new(RefCell::new(0));
+        let inner = Rc::new(RefCell::new(0));
+
+        scope(
+            {
+                let outer = Rc::clone(&outer);
+                move |_| *outer.borrow_mut() += 1
+            },
+            || {
+                crate::string::trim(" a");
+
+                scope(
+                    {
+                        let inner = Rc::clone(&inner);
+                        move |_| *inner.borrow_mut() += 1
+                    },
+                    || crate::string::trim("b "),
+                );
+
+                crate::string::trim(" c ");
+            },
+        );
+
+        crate::string::trim(" d ");
+
+        assert_eq!(*outer.borrow(), 2);
+        assert_eq!(*inner.borrow(), 1);
+    }
+
+    #[test]
+    fn no_scope() {
+        let val = deserialize::<Bar, _>(&mut serde_json::Deserializer::from_str(r#"" x ""#));
+        assert_eq!(val.unwrap().0, "x");
+    }
+}
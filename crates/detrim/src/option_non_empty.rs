@@ -0,0 +1,449 @@
+use alloc::str;
+use core::{any::type_name, fmt, marker::PhantomData, str::FromStr};
+
+use serde::{
+    de::{
+        self,
+        value::{
+            BorrowedStrDeserializer, EnumAccessDeserializer, MapAccessDeserializer,
+            SeqAccessDeserializer, StrDeserializer,
+        },
+    },
+    forward_to_deserialize_any, Deserialize, Deserializer,
+};
+
+use crate::{
+    parse::{forward_native, parse_trimmed},
+    string::trim,
+};
+
+/// Deserializer for a trimmed string, parsing it when `T` asks for a boolean or number.
+///
+/// Other requests are passed to the inner string deserializer `D`.
+struct TrimmedDeserializer<'a, D> {
+    original: &'a str,
+    trimmed: &'a str,
+    de: D,
+}
+
+macro_rules! deserialize_parse {
+    ($($method:ident => $visit:ident),* $(,)?) => {
+        $(
+            fn $method<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                visitor.$visit(parse_trimmed(self.original, self.trimmed)?)
+            }
+        )*
+    };
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for TrimmedDeserializer<'_, D> {
+    type Error = D::Error;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.de.deserialize_any(visitor)
+    }
+
+    deserialize_parse! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+    }
+
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.de.deserialize_enum(name, variants, visitor)
+    }
+
+    forward_to_deserialize_any! {
+        char str string bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// Visitor that maps blank strings to `None`, otherwise deserializing `T` from the trimmed string.
+///
+/// Non-string values are deserialized directly into `T`.
+struct OptionNonEmptyVisitor<T>(PhantomData<fn() -> T>);
+
+impl<'de, T: Deserialize<'de>> de::Visitor<'de> for OptionNonEmptyVisitor<T> {
+    type Value = Option<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "an optional {}", type_name::<T>())
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_any(self)
+    }
+
+    fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+        match trim(val) {
+            "" => Ok(None),
+            trimmed => T::deserialize(TrimmedDeserializer {
+                original: val,
+                trimmed,
+                de: StrDeserializer::new(trimmed),
+            })
+            .map(Some),
+        }
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, val: &'de str) -> Result<Self::Value, E> {
+        match trim(val) {
+            "" => Ok(None),
+            trimmed => T::deserialize(TrimmedDeserializer {
+                original: val,
+                trimmed,
+                de: BorrowedStrDeserializer::new(trimmed),
+            })
+            .map(Some),
+        }
+    }
+
+    fn visit_bytes<E: de::Error>(self, val: &[u8]) -> Result<Self::Value, E> {
+        match str::from_utf8(val) {
+            Ok(val) => self.visit_str(val),
+            Err(_) => Err(de::Error::invalid_value(de::Unexpected::Bytes(val), &self)),
+        }
+    }
+
+    forward_native!(|de| T::deserialize(de).map(Some));
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        T::deserialize(SeqAccessDeserializer::new(seq)).map(Some)
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        T::deserialize(MapAccessDeserializer::new(map)).map(Some)
+    }
+
+    fn visit_enum<A: de::EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        T::deserialize(EnumAccessDeserializer::new(data)).map(Some)
+    }
+}
+
+/// Visitor that maps blank strings to `None`, otherwise parsing the trimmed string into `T`.
+struct OptionNonEmptyParseVisitor<T>(PhantomData<fn() -> T>);
+
+impl<'de, T> de::Visitor<'de> for OptionNonEmptyParseVisitor<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    type Value = Option<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "an optional string containing a {}",
+            type_name::<T>(),
+        )
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_str(self)
+    }
+
+    fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+        match trim(val) {
+            "" => Ok(None),
+            trimmed => parse_trimmed(val, trimmed).map(Some),
+        }
+    }
+
+    fn visit_bytes<E: de::Error>(self, val: &[u8]) -> Result<Self::Value, E> {
+        match str::from_utf8(val) {
+            Ok(val) => self.visit_str(val),
+            Err(_) => Err(de::Error::invalid_value(de::Unexpected::Bytes(val), &self)),
+        }
+    }
+}
+
+/// Deserializes an optional `T`, returning `None` if given a string that is empty after trimming.
+///
+/// Non-blank strings are trimmed and then passed to `T`'s [`Deserialize`] implementation, which
+/// suits types that deserialize from strings, such as unit-only enums. If `T` asks for a boolean or
+/// number, the trimmed string is parsed using its [`FromStr`] implementation. Other values, such as
+/// numbers, sequences, and maps, are passed to `T` unchanged. Since the input type is detected at
+/// runtime, this is only supported by self-describing formats.
+///
+/// To accept only strings, use [`option_non_empty_parse()`].
+///
+/// # Examples
+///
+/// ```
+/// #[derive(Debug, PartialEq, serde::Deserialize)]
+/// #[serde(rename_all = "lowercase")]
+/// enum Size {
+///     Small,
+///     Large,
+/// }
+///
+/// #[derive(Debug, serde::Deserialize)]
+/// struct Form {
+///     #[serde(deserialize_with = "detrim::option_non_empty")]
+///     size: Option<Size>,
+/// }
+///
+/// let form = serde_json::from_str::<Form>(r#"{ "size": " large " }"#).unwrap();
+/// assert_eq!(form.size, Some(Size::Large));
+///
+/// let form = serde_json::from_str::<Form>(r#"{ "size": "  " }"#).unwrap();
+/// assert_eq!(form.size, None);
+/// ```
+pub fn option_non_empty<'de, T, D>(de: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    de.deserialize_option(OptionNonEmptyVisitor(PhantomData))
+}
+
+/// Parses an optional `T` using its [`FromStr`] implementation, returning `None` if given a string
+/// that is empty after trimming.
+///
+/// # Examples
+///
+/// ```
+/// #[derive(Debug, serde::Deserialize)]
+/// struct Form {
+///     #[serde(deserialize_with = "detrim::option_non_empty_parse")]
+///     age: Option<u32>,
+/// }
+///
+/// let form = serde_json::from_str::<Form>(r#"{ "age": " 42 " }"#).unwrap();
+/// assert_eq!(form.age, Some(42));
+///
+/// let form = serde_json::from_str::<Form>(r#"{ "age": "" }"#).unwrap();
+/// assert_eq!(form.age, None);
+/// ```
+pub fn option_non_empty_parse<'de, T, D>(de: D) -> Result<Option<T>, D::Error>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+    D: Deserializer<'de>,
+{
+    de.deserialize_option(OptionNonEmptyParseVisitor(PhantomData))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{
+        string::{String, ToString as _},
+        vec,
+        vec::Vec,
+    };
+
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[test]
+    fn option_non_empty() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        enum Size {
+            Small,
+            Large,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::option_non_empty")]
+            foo: Option<Size>,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": 1 }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": "Medium" }"#).unwrap_err();
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": null }"#)
+                .unwrap()
+                .foo,
+            None,
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": "" }"#).unwrap().foo,
+            None,
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": "   " }"#)
+                .unwrap()
+                .foo,
+            None,
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": "Small" }"#)
+                .unwrap()
+                .foo,
+            Some(Size::Small),
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": " Large\n" }"#)
+                .unwrap()
+                .foo,
+            Some(Size::Large),
+        );
+    }
+
+    #[test]
+    fn option_non_empty_native() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::option_non_empty")]
+            foo: Option<u32>,
+            #[serde(deserialize_with = "super::option_non_empty")]
+            bar: Option<String>,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": "1 2", "bar": null }"#).unwrap_err();
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": " ", "bar": " " }"#).unwrap(),
+            Foo {
+                foo: None,
+                bar: None
+            },
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": 1, "bar": " bar " }"#).unwrap(),
+            Foo {
+                foo: Some(1),
+                bar: Some(String::from("bar"))
+            },
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": " 5 ", "bar": null }"#).unwrap(),
+            Foo {
+                foo: Some(5),
+                bar: None
+            },
+        );
+
+        let err = serde_json::from_str::<Foo>(r#"{ "foo": " x ", "bar": null }"#).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with(r#"invalid value: string " x ", expected a string containing a u32"#),
+            "{err}",
+        );
+    }
+
+    #[test]
+    fn option_non_empty_compound() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Point {
+            x: u32,
+            y: u32,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        enum Shape {
+            Dot,
+            Line(u32),
+        }
+
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(default, deserialize_with = "super::option_non_empty")]
+            list: Option<Vec<u32>>,
+            #[serde(default, deserialize_with = "super::option_non_empty")]
+            point: Option<Point>,
+            #[serde(default, deserialize_with = "super::option_non_empty")]
+            shape: Option<Shape>,
+        }
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(
+                r#"{ "list": [1, 2], "point": { "x": 1, "y": 2 }, "shape": { "Line": 3 } }"#
+            )
+            .unwrap(),
+            Foo {
+                list: Some(vec![1, 2]),
+                point: Some(Point { x: 1, y: 2 }),
+                shape: Some(Shape::Line(3)),
+            },
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "list": " ", "point": "", "shape": " Dot " }"#)
+                .unwrap(),
+            Foo {
+                list: None,
+                point: None,
+                shape: Some(Shape::Dot),
+            },
+        );
+    }
+
+    #[test]
+    fn option_non_empty_parse() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::option_non_empty_parse")]
+            foo: Option<u32>,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": 1 }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": "1 2" }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": "-1" }"#).unwrap_err();
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": null }"#)
+                .unwrap()
+                .foo,
+            None,
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": "" }"#).unwrap().foo,
+            None,
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": "  " }"#)
+                .unwrap()
+                .foo,
+            None,
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": " 42 " }"#)
+                .unwrap()
+                .foo,
+            Some(42),
+        );
+
+        let err = serde_json::from_str::<Foo>(r#"{ "foo": " 4 2 " }"#).unwrap_err();
+        assert!(
+            err.to_string().starts_with(
+                r#"invalid value: string " 4 2 ", expected a string containing a u32"#
+            ),
+            "{err}",
+        );
+    }
+}
@@ -0,0 +1,436 @@
+use alloc::{borrow::ToOwned as _, str, string::ToString as _};
+use core::{any::type_name, fmt, marker::PhantomData, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer};
+
+use crate::{error::Error, string::trim};
+
+/// Trims `val` and parses it using `T`'s [`FromStr`] implementation.
+pub(crate) fn trim_parse<T, E>(val: &str) -> Result<T, E>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+    E: de::Error,
+{
+    parse_trimmed(val, trim(val))
+}
+
+/// Parses `trimmed` using `T`'s [`FromStr`] implementation, reporting `original` on error.
+pub(crate) fn parse_trimmed<T, E>(original: &str, trimmed: &str) -> Result<T, E>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+    E: de::Error,
+{
+    trimmed.parse().map_err(|err: T::Err| {
+        Error::Parse {
+            original: original.to_owned(),
+            ty: type_name::<T>(),
+            reason: err.to_string(),
+        }
+        .into_de_error()
+    })
+}
+
+/// Visitor that trims a string and parses it using `T`'s [`FromStr`] implementation.
+struct ParseVisitor<T>(PhantomData<fn() -> T>);
+
+impl<'de, T> de::Visitor<'de> for ParseVisitor<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "a string containing a {}", type_name::<T>())
+    }
+
+    fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+        trim_parse(val)
+    }
+
+    fn visit_bytes<E: de::Error>(self, val: &[u8]) -> Result<Self::Value, E> {
+        match str::from_utf8(val) {
+            Ok(val) => trim_parse(val),
+            Err(_) => Err(de::Error::invalid_value(de::Unexpected::Bytes(val), &self)),
+        }
+    }
+}
+
+/// Visitor like [`ParseVisitor`] that also deserializes non-string scalars directly into `T`.
+struct ParseOrNativeVisitor<T>(PhantomData<fn() -> T>);
+
+/// Implements the visitor methods for non-string scalars, evaluating `$body` with `$de` bound to a
+/// deserializer for the visited value.
+macro_rules! forward_native {
+    (|$de:ident| $body:expr) => {
+        forward_native!(@methods |$de| $body;
+            visit_bool(bool),
+            visit_i64(i64),
+            visit_i128(i128),
+            visit_u64(u64),
+            visit_u128(u128),
+            visit_f64(f64),
+            visit_char(char),
+        );
+    };
+    (@methods |$de:ident| $body:expr; $($method:ident($ty:ty),)*) => {
+        $(
+            fn $method<E: ::serde::de::Error>(self, val: $ty) -> Result<Self::Value, E> {
+                let $de = ::serde::de::IntoDeserializer::into_deserializer(val);
+                $body
+            }
+        )*
+    };
+}
+
+pub(crate) use forward_native;
+
+impl<'de, T> de::Visitor<'de> for ParseOrNativeVisitor<T>
+where
+    T: FromStr + Deserialize<'de>,
+    T::Err: fmt::Display,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "a {} or a string containing one",
+            type_name::<T>()
+        )
+    }
+
+    fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+        trim_parse(val)
+    }
+
+    fn visit_bytes<E: de::Error>(self, val: &[u8]) -> Result<Self::Value, E> {
+        match str::from_utf8(val) {
+            Ok(val) => trim_parse(val),
+            Err(_) => Err(de::Error::invalid_value(de::Unexpected::Bytes(val), &self)),
+        }
+    }
+
+    forward_native!(|de| T::deserialize(de));
+
+    #[cfg(feature = "arbitrary_precision")]
+    fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        // serde_json's `arbitrary_precision` feature presents numbers as a map
+        let number = serde_json::Number::deserialize(de::value::MapAccessDeserializer::new(map))?;
+        trim_parse(&number.to_string())
+    }
+}
+
+impl<'de, T> de::DeserializeSeed<'de> for ParseVisitor<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    type Value = T;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_str(self)
+    }
+}
+
+impl<'de, T> de::DeserializeSeed<'de> for ParseOrNativeVisitor<T>
+where
+    T: FromStr + Deserialize<'de>,
+    T::Err: fmt::Display,
+{
+    type Value = T;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_any(self)
+    }
+}
+
+/// Visitor that deserializes `null` as `None`, or defers to the seed `S`.
+struct OptionVisitor<S>(S);
+
+impl<'de, S: de::DeserializeSeed<'de>> de::Visitor<'de> for OptionVisitor<S> {
+    type Value = Option<S::Value>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "an optional {}", type_name::<S::Value>())
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        self.0.deserialize(de).map(Some)
+    }
+}
+
+/// Trims a string during deserialization, then parses it using `T`'s [`FromStr`] implementation.
+///
+/// Parse errors are reported as [`Error::Parse`] through [`de::Error::invalid_value`], showing the
+/// value before trimming along with the target type and the reason it failed to parse.
+///
+/// # Examples
+///
+/// ```
+/// #[derive(Debug, serde::Deserialize)]
+/// struct Config {
+///     #[serde(deserialize_with = "detrim::parse")]
+///     port: u16,
+/// }
+///
+/// let config = serde_json::from_str::<Config>(r#"{ "port": " 8080 " }"#).unwrap();
+/// assert_eq!(config.port, 8080);
+/// ```
+pub fn parse<'de, T, D>(de: D) -> Result<T, D::Error>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+    D: Deserializer<'de>,
+{
+    de.deserialize_str(ParseVisitor(PhantomData))
+}
+
+/// Trims an optional string during deserialization, then parses it using `T`'s [`FromStr`]
+/// implementation.
+///
+/// See [`parse()`] for more.
+pub fn option_parse<'de, T, D>(de: D) -> Result<Option<T>, D::Error>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+    D: Deserializer<'de>,
+{
+    de.deserialize_option(OptionVisitor(ParseVisitor(PhantomData)))
+}
+
+/// Trims and parses a string like [`parse()`], or deserializes a non-string value directly.
+///
+/// Booleans, numbers, and chars are passed to `T`'s [`Deserialize`] implementation unchanged. Since
+/// the input type is detected at runtime, this is only supported by self-describing formats.
+///
+/// If serde_json's `arbitrary_precision` feature is enabled, enable the `arbitrary_precision`
+/// crate feature too, so that numbers are parsed from their string form.
+///
+/// # Examples
+///
+/// ```
+/// #[derive(Debug, serde::Deserialize)]
+/// struct Config {
+///     #[serde(deserialize_with = "detrim::parse_or_native")]
+///     port: u16,
+/// }
+///
+/// let config = serde_json::from_str::<Config>(r#"{ "port": " 8080 " }"#).unwrap();
+/// assert_eq!(config.port, 8080);
+///
+/// let config = serde_json::from_str::<Config>(r#"{ "port": 8080 }"#).unwrap();
+/// assert_eq!(config.port, 8080);
+/// ```
+pub fn parse_or_native<'de, T, D>(de: D) -> Result<T, D::Error>
+where
+    T: FromStr + Deserialize<'de>,
+    T::Err: fmt::Display,
+    D: Deserializer<'de>,
+{
+    de.deserialize_any(ParseOrNativeVisitor(PhantomData))
+}
+
+/// Trims and parses an optional string like [`option_parse()`], or deserializes a non-string value
+/// directly.
+///
+/// See [`parse_or_native()`] for more.
+pub fn option_parse_or_native<'de, T, D>(de: D) -> Result<Option<T>, D::Error>
+where
+    T: FromStr + Deserialize<'de>,
+    T::Err: fmt::Display,
+    D: Deserializer<'de>,
+{
+    de.deserialize_option(OptionVisitor(ParseOrNativeVisitor(PhantomData)))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString as _;
+
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[test]
+    fn parse() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::parse")]
+            foo: u16,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": 1 }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": null }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": "" }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": "1 2" }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": "65536" }"#).unwrap_err();
+
+        let err = serde_json::from_str::<Foo>(r#"{ "foo": " 80a " }"#).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with(r#"invalid value: string " 80a ", expected a string containing a u16 ("#));
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": "8080" }"#)
+                .unwrap()
+                .foo,
+            8080,
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": " 8080 " }"#)
+                .unwrap()
+                .foo,
+            8080,
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn parse_ip_addr() {
+        use std::net::IpAddr;
+
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::parse")]
+            foo: IpAddr,
+        }
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": "10.0.0.1\n" }"#)
+                .unwrap()
+                .foo,
+            IpAddr::from([10, 0, 0, 1]),
+        );
+    }
+
+    #[test]
+    fn option_parse() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::option_parse")]
+            foo: Option<u16>,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": 1 }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": " " }"#).unwrap_err();
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": null }"#)
+                .unwrap()
+                .foo,
+            None,
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": " 8080 " }"#)
+                .unwrap()
+                .foo,
+            Some(8080),
+        );
+    }
+
+    #[test]
+    fn parse_or_native() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::parse_or_native")]
+            foo: u16,
+            #[serde(deserialize_with = "super::parse_or_native")]
+            bar: f64,
+            #[serde(deserialize_with = "super::parse_or_native")]
+            baz: bool,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": -1, "bar": 1, "baz": true }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": 1, "bar": 1, "baz": 1 }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": [], "bar": 1, "baz": true }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": { "a": "1" }, "bar": 1, "baz": true }"#)
+            .unwrap_err();
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": 8080, "bar": 1.5, "baz": true }"#).unwrap(),
+            Foo {
+                foo: 8080,
+                bar: 1.5,
+                baz: true
+            },
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": " 8080", "bar": "1.5 ", "baz": " true " }"#)
+                .unwrap(),
+            Foo {
+                foo: 8080,
+                bar: 1.5,
+                baz: true
+            },
+        );
+    }
+
+    #[cfg(feature = "arbitrary_precision")]
+    #[test]
+    fn parse_or_native_arbitrary_precision() {
+        // numbers keep their digits, so serde_json's `arbitrary_precision` feature is enabled
+        let num = serde_json::from_str::<serde_json::Number>("1.10").unwrap();
+        assert_eq!(num.to_string(), "1.10");
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::parse_or_native")]
+            foo: u128,
+            #[serde(deserialize_with = "super::parse_or_native")]
+            bar: f64,
+        }
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(
+                r#"{ "foo": 340282366920938463463374607431768211455, "bar": 1.5e3 }"#
+            )
+            .unwrap(),
+            Foo {
+                foo: u128::MAX,
+                bar: 1500.0,
+            },
+        );
+
+        serde_json::from_str::<Foo>(r#"{ "foo": 1.5, "bar": 1 }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": { "a": "1" }, "bar": 1 }"#).unwrap_err();
+    }
+
+    #[test]
+    fn option_parse_or_native() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::option_parse_or_native")]
+            foo: Option<u16>,
+        }
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": null }"#)
+                .unwrap()
+                .foo,
+            None,
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": 8080 }"#)
+                .unwrap()
+                .foo,
+            Some(8080),
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": " 8080 " }"#)
+                .unwrap()
+                .foo,
+            Some(8080),
+        );
+    }
+}
@@ -0,0 +1,317 @@
+//! Trimming deserializers for filesystem paths.
+//!
+//! Paths are trimmed, then lexically normalized without touching the filesystem:
+//!
+//! - `.` components and trailing separators are removed, so `./logs/` becomes `logs`;
+//! - `..` components remove the preceding component, so `a/b/../c` becomes `a/c`;
+//! - `..` components at the root are removed, so `/../etc` becomes `/etc`, but leading `..`
+//!   components of relative paths are kept, since there is nothing to remove;
+//! - a relative path with no components left becomes `.`.
+//!
+//! Since symlinks are not resolved, `a/..` may refer to a different directory than `.` on disk.
+//!
+//! Paths that are empty after trimming are rejected. The `expand_` functions also expand a leading
+//! `~` using the home directory provided by a [`HomeDir`] implementation.
+//!
+//! Requires the `std` crate feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::path::{Path, PathBuf};
+//!
+//! #[derive(Debug, serde::Deserialize)]
+//! struct Config {
+//!     #[serde(deserialize_with = "detrim::path::path_buf")]
+//!     data_dir: PathBuf,
+//! }
+//!
+//! let json = r#"{ "data_dir": " ./var/lib/../data/ " }"#;
+//! let config = serde_json::from_str::<Config>(json).unwrap();
+//! assert_eq!(config.data_dir, Path::new("var/data"));
+//!
+//! serde_json::from_str::<Config>(r#"{ "data_dir": "  " }"#).unwrap_err();
+//! ```
+
+use std::path::{Component, Path, PathBuf};
+
+use serde::{de, Deserialize as _, Deserializer};
+
+use crate::{
+    error::Error,
+    string::{trim, trim_in_place},
+};
+
+/// Home directory that a leading `~` expands to in [`expand_path_buf()`] and
+/// [`expand_option_path_buf()`].
+///
+/// # Examples
+///
+/// ```
+/// use std::path::PathBuf;
+///
+/// use detrim::path::HomeDir;
+///
+/// enum EnvHome {}
+///
+/// impl HomeDir for EnvHome {
+///     fn home_dir() -> Option<PathBuf> {
+///         std::env::var_os("HOME").map(PathBuf::from)
+///     }
+/// }
+///
+/// #[derive(Debug, serde::Deserialize)]
+/// struct Config {
+///     #[serde(deserialize_with = "detrim::path::expand_path_buf::<EnvHome, _>")]
+///     cache_dir: PathBuf,
+/// }
+/// ```
+pub trait HomeDir {
+    /// Returns the home directory, or `None` if it is unknown.
+    fn home_dir() -> Option<PathBuf>;
+}
+
+/// Lexically normalizes `path`.
+///
+/// See [module docs](self) for the rules.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                Some(Component::RootDir | Component::Prefix(_)) => {}
+                Some(Component::ParentDir | Component::CurDir) | None => {
+                    normalized.push(component);
+                }
+            },
+            Component::Prefix(_) | Component::RootDir | Component::Normal(_) => {
+                normalized.push(component);
+            }
+        }
+    }
+
+    if normalized.as_os_str().is_empty() {
+        normalized.push(Component::CurDir);
+    }
+
+    normalized
+}
+
+/// Trims `val` and expands a leading `~` to the home directory provided by `H`.
+///
+/// Only `~` on its own or followed by a separator is expanded, so `~user` is left unchanged.
+/// Errors carry `val` as it was before trimming.
+fn expand<H: HomeDir>(val: String) -> Result<PathBuf, Error> {
+    let trimmed = trim(&val);
+
+    if trimmed.is_empty() {
+        return Err(Error::Empty {
+            original: val,
+            index: None,
+        });
+    }
+
+    let rest = match trimmed.strip_prefix('~') {
+        Some("") => "",
+        Some(rest) if rest.starts_with(std::path::is_separator) => {
+            rest.trim_start_matches(std::path::is_separator)
+        }
+        _ => return Ok(PathBuf::from(trimmed)),
+    };
+
+    match H::home_dir() {
+        Some(home) => Ok(home.join(rest)),
+        None => Err(Error::NoHomeDir { original: val }),
+    }
+}
+
+/// Trims `val` in place, returning error if it ends up empty.
+fn trim_non_empty<E: de::Error>(mut val: String) -> Result<String, E> {
+    if val.trim().is_empty() {
+        return Err(Error::Empty {
+            original: val,
+            index: None,
+        }
+        .into_de_error());
+    }
+
+    trim_in_place(&mut val);
+    Ok(val)
+}
+
+/// Trims and normalizes a path during deserialization, returning error if it ends up empty.
+///
+/// See [module docs](self) for more.
+pub fn path_buf<'de, D: Deserializer<'de>>(de: D) -> Result<PathBuf, D::Error> {
+    let val = trim_non_empty(String::deserialize(de)?)?;
+    Ok(normalize(Path::new(&val)))
+}
+
+/// Trims and normalizes an optional path during deserialization, returning error if it ends up
+/// empty.
+///
+/// See [module docs](self) for more.
+pub fn option_path_buf<'de, D: Deserializer<'de>>(de: D) -> Result<Option<PathBuf>, D::Error> {
+    Option::<String>::deserialize(de)?
+        .map(|val| trim_non_empty(val).map(|val| normalize(Path::new(&val))))
+        .transpose()
+}
+
+/// Trims, expands a leading `~`, and normalizes a path during deserialization, returning error if
+/// it ends up empty.
+///
+/// The home directory is provided by `H`. Returns error if the path starts with `~` but the home
+/// directory is unknown. See [module docs](self) for more.
+pub fn expand_path_buf<'de, H, D>(de: D) -> Result<PathBuf, D::Error>
+where
+    H: HomeDir,
+    D: Deserializer<'de>,
+{
+    let path = expand::<H>(String::deserialize(de)?).map_err(Error::into_de_error)?;
+    Ok(normalize(&path))
+}
+
+/// Trims, expands a leading `~`, and normalizes an optional path during deserialization,
+/// returning error if it ends up empty.
+///
+/// See [`expand_path_buf()`] for details.
+pub fn expand_option_path_buf<'de, H, D>(de: D) -> Result<Option<PathBuf>, D::Error>
+where
+    H: HomeDir,
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(de)?
+        .map(|val| {
+            let path = expand::<H>(val).map_err(Error::into_de_error)?;
+            Ok(normalize(&path))
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    enum Home {}
+
+    impl HomeDir for Home {
+        fn home_dir() -> Option<PathBuf> {
+            Some(PathBuf::from("/home/ferris"))
+        }
+    }
+
+    enum NoHome {}
+
+    impl HomeDir for NoHome {
+        fn home_dir() -> Option<PathBuf> {
+            None
+        }
+    }
+
+    #[test]
+    fn normalize() {
+        for (path, expected) in [
+            ("/etc/", "/etc"),
+            ("./logs/", "logs"),
+            ("a/./b//c", "a/b/c"),
+            ("a/b/../c", "a/c"),
+            ("a/b/../../..", ".."),
+            ("../../a", "../../a"),
+            ("/../etc/../..", "/"),
+            ("a/..", "."),
+            (".", "."),
+            ("~user/a", "~user/a"),
+        ] {
+            assert_eq!(
+                super::normalize(Path::new(path)),
+                Path::new(expected),
+                "{path}"
+            );
+        }
+    }
+
+    #[test]
+    fn path_buf() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::path_buf")]
+            foo: PathBuf,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": 1 }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": "" }"#).unwrap_err();
+
+        let err = serde_json::from_str::<Foo>(r#"{ "foo": " \t" }"#).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with(r#"invalid value: string " \t""#));
+
+        let foo = serde_json::from_str::<Foo>(r#"{ "foo": "  /var/log/../lib/ " }"#).unwrap();
+        assert_eq!(foo.foo, Path::new("/var/lib"));
+
+        // `~` is not expanded
+        let foo = serde_json::from_str::<Foo>(r#"{ "foo": "~/a" }"#).unwrap();
+        assert_eq!(foo.foo, Path::new("~/a"));
+    }
+
+    #[test]
+    fn option_path_buf() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::option_path_buf")]
+            foo: Option<PathBuf>,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": " " }"#).unwrap_err();
+
+        let foo = serde_json::from_str::<Foo>(r#"{ "foo": null }"#).unwrap();
+        assert_eq!(foo.foo, None);
+
+        let foo = serde_json::from_str::<Foo>(r#"{ "foo": " ./a/ " }"#).unwrap();
+        assert_eq!(foo.foo.as_deref(), Some(Path::new("a")));
+    }
+
+    #[test]
+    fn expand_path_buf() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::expand_path_buf::<Home, _>")]
+            foo: PathBuf,
+            #[serde(deserialize_with = "super::expand_option_path_buf::<NoHome, _>")]
+            bar: Option<PathBuf>,
+        }
+
+        for (path, expected) in [
+            (" ~ ", "/home/ferris"),
+            ("~/", "/home/ferris"),
+            ("~/.config/../.cache/", "/home/ferris/.cache"),
+            ("~//a", "/home/ferris/a"),
+            ("~/../..", "/"),
+            ("~user/a", "~user/a"),
+            ("a/~", "a/~"),
+        ] {
+            let json = serde_json::json!({ "foo": path, "bar": "/tmp/" });
+            let foo = serde_json::from_value::<Foo>(json).unwrap();
+            assert_eq!(foo.foo, Path::new(expected), "{path}");
+            assert_eq!(foo.bar.as_deref(), Some(Path::new("/tmp")));
+        }
+
+        let err = serde_json::from_str::<Foo>(r#"{ "foo": "a", "bar": " ~/a" }"#).unwrap_err();
+        assert!(err.to_string().contains("home directory is unknown"));
+        assert!(err
+            .to_string()
+            .starts_with(r#"invalid value: string " ~/a""#));
+
+        let foo = serde_json::from_str::<Foo>(r#"{ "foo": "a", "bar": null }"#).unwrap();
+        assert_eq!(foo.bar, None);
+
+        serde_json::from_str::<Foo>(r#"{ "foo": "  ", "bar": null }"#).unwrap_err();
+    }
+}
@@ -0,0 +1,330 @@
+//! Policies for collection items that are empty after trimming.
+//!
+//! The collection deserializers at the crate root, like [`vec_string()`](crate::vec_string), keep
+//! empty items. The types in this module provide the same deserializers with a different policy:
+//! [`NonEmpty`] returns an error naming the index of the first empty item, and [`SkipEmpty`]
+//! removes empty items. If `AT_LEAST_ONE` is set, an empty collection is also an error.
+//!
+//! Each policy provides `vec_string()`, `btreeset_string()`, `hashset_string()` (behind the `std`
+//! crate feature), and `hashbrown_hashset_string()` (behind the `hashbrown` crate feature).
+//!
+//! # Examples
+//!
+//! ```
+//! #[derive(Debug, serde::Deserialize)]
+//! struct Email {
+//!     #[serde(deserialize_with = "detrim::seq::NonEmpty::<true>::vec_string")]
+//!     recipients: Vec<String>,
+//!     #[serde(deserialize_with = "detrim::seq::SkipEmpty::<false>::vec_string")]
+//!     tags: Vec<String>,
+//! }
+//!
+//! let json = r#"{ "recipients": [" ferris@example.com "], "tags": ["crab", " "] }"#;
+//! let email = serde_json::from_str::<Email>(json).unwrap();
+//! assert_eq!(email.recipients, ["ferris@example.com"]);
+//! assert_eq!(email.tags, ["crab"]);
+//!
+//! serde_json::from_str::<Email>(r#"{ "recipients": [], "tags": [] }"#).unwrap_err();
+//! serde_json::from_str::<Email>(r#"{ "recipients": ["a", ""], "tags": [] }"#).unwrap_err();
+//! ```
+
+use alloc::{collections::BTreeSet, string::String, vec::Vec};
+use core::{cmp, fmt, marker::PhantomData};
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+
+use serde::{de, Deserialize as _, Deserializer};
+
+use crate::{error::Error, string::trim_in_place};
+
+/// Collection deserializers that return an error for items that are empty after trimming.
+///
+/// The error includes the index of the first empty item. If `AT_LEAST_ONE` is set, an empty
+/// collection is also an error.
+///
+/// See [module docs](self) for more.
+#[derive(Debug)]
+pub enum NonEmpty<const AT_LEAST_ONE: bool> {}
+
+/// Collection deserializers that remove items that are empty after trimming.
+///
+/// If `AT_LEAST_ONE` is set, it is an error for no items to remain.
+///
+/// See [module docs](self) for more.
+#[derive(Debug)]
+pub enum SkipEmpty<const AT_LEAST_ONE: bool> {}
+
+macro_rules! impl_policy {
+    ($ty:ident, $empty_items:expr) => {
+        impl<const AT_LEAST_ONE: bool> $ty<AT_LEAST_ONE> {
+            /// Trims list of strings during deserialization.
+            pub fn vec_string<'de, D: Deserializer<'de>>(de: D) -> Result<Vec<String>, D::Error> {
+                de.deserialize_seq(TrimSeqVisitor::new($empty_items, AT_LEAST_ONE))
+            }
+
+            /// Trims set of strings during deserialization.
+            ///
+            /// Strings are deduplicated _after_ being trimmed.
+            #[cfg(feature = "std")]
+            pub fn hashset_string<'de, D: Deserializer<'de>>(
+                de: D,
+            ) -> Result<HashSet<String>, D::Error> {
+                de.deserialize_seq(TrimSeqVisitor::new($empty_items, AT_LEAST_ONE))
+            }
+
+            /// Trims set of strings into a `BTreeSet` during deserialization.
+            ///
+            /// Strings are deduplicated _after_ being trimmed. Does not require the `std` crate
+            /// feature.
+            pub fn btreeset_string<'de, D: Deserializer<'de>>(
+                de: D,
+            ) -> Result<BTreeSet<String>, D::Error> {
+                de.deserialize_seq(TrimSeqVisitor::new($empty_items, AT_LEAST_ONE))
+            }
+
+            /// Trims set of strings into a `hashbrown` `HashSet` during deserialization.
+            ///
+            /// Strings are deduplicated _after_ being trimmed. Does not require the `std` crate
+            /// feature; the hasher `S` is inferred from the field type.
+            #[cfg(feature = "hashbrown")]
+            pub fn hashbrown_hashset_string<'de, S, D>(
+                de: D,
+            ) -> Result<hashbrown::HashSet<String, S>, D::Error>
+            where
+                S: core::hash::BuildHasher + Default,
+                D: Deserializer<'de>,
+            {
+                de.deserialize_seq(TrimSeqVisitor::new($empty_items, AT_LEAST_ONE))
+            }
+        }
+    };
+}
+
+impl_policy!(NonEmpty, EmptyItems::Reject);
+impl_policy!(SkipEmpty, EmptyItems::Skip);
+
+/// Upper bound on pre-allocated capacity, in items, regardless of a sequence's size hint.
+const MAX_PREALLOC: usize = 4096;
+
+/// Returns the capacity to pre-allocate for a sequence or map with the given size hint.
+pub(crate) fn capacity(size_hint: Option<usize>) -> usize {
+    cmp::min(size_hint.unwrap_or(0), MAX_PREALLOC)
+}
+
+/// Visitor that deserializes each item of a sequence with a seed, collecting them into a `Vec`.
+///
+/// The seed for each item is built by calling `seed` with the item's index.
+pub(crate) struct VecSeedVisitor<F> {
+    expecting: &'static str,
+    seed: F,
+}
+
+impl<F> VecSeedVisitor<F> {
+    pub(crate) fn new(expecting: &'static str, seed: F) -> Self {
+        Self { expecting, seed }
+    }
+}
+
+impl<'de, F, S> de::Visitor<'de> for VecSeedVisitor<F>
+where
+    F: FnMut(usize) -> S,
+    S: de::DeserializeSeed<'de>,
+{
+    type Value = Vec<S::Value>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.expecting)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(mut self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut list = Vec::with_capacity(capacity(seq.size_hint()));
+
+        while let Some(item) = seq.next_element_seed((self.seed)(list.len()))? {
+            list.push(item);
+        }
+
+        Ok(list)
+    }
+}
+
+/// Handling of items that are empty after trimming.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EmptyItems {
+    /// Keep the empty item.
+    Keep,
+
+    /// Return an error naming the index of the empty item.
+    Reject,
+
+    /// Remove the empty item from the collection.
+    Skip,
+}
+
+/// Collection that trimmed strings can be inserted into as they are deserialized.
+pub(crate) trait StringCollection: Sized {
+    /// Creates an empty collection with space for at least `capacity` items.
+    fn with_capacity(capacity: usize) -> Self;
+
+    /// Inserts a trimmed string into the collection.
+    fn insert(&mut self, item: String);
+
+    /// Returns true if the collection contains no items.
+    fn is_empty(&self) -> bool;
+}
+
+impl StringCollection for Vec<String> {
+    fn with_capacity(capacity: usize) -> Self {
+        Vec::with_capacity(capacity)
+    }
+
+    fn insert(&mut self, item: String) {
+        self.push(item);
+    }
+
+    fn is_empty(&self) -> bool {
+        Vec::is_empty(self)
+    }
+}
+
+impl StringCollection for BTreeSet<String> {
+    fn with_capacity(_capacity: usize) -> Self {
+        BTreeSet::new()
+    }
+
+    fn insert(&mut self, item: String) {
+        BTreeSet::insert(self, item);
+    }
+
+    fn is_empty(&self) -> bool {
+        BTreeSet::is_empty(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl StringCollection for HashSet<String> {
+    fn with_capacity(capacity: usize) -> Self {
+        HashSet::with_capacity(capacity)
+    }
+
+    fn insert(&mut self, item: String) {
+        HashSet::insert(self, item);
+    }
+
+    fn is_empty(&self) -> bool {
+        HashSet::is_empty(self)
+    }
+}
+
+#[cfg(feature = "hashbrown")]
+impl<S: core::hash::BuildHasher + Default> StringCollection for hashbrown::HashSet<String, S> {
+    fn with_capacity(capacity: usize) -> Self {
+        hashbrown::HashSet::with_capacity_and_hasher(capacity, S::default())
+    }
+
+    fn insert(&mut self, item: String) {
+        hashbrown::HashSet::insert(self, item);
+    }
+
+    fn is_empty(&self) -> bool {
+        hashbrown::HashSet::is_empty(self)
+    }
+}
+
+/// Seed that deserializes a trimmed string.
+///
+/// Trimming happens inside the element's deserialization, rather than after it is returned, so
+/// that observers see the element's path.
+pub(crate) struct TrimStringSeed;
+
+impl<'de> de::DeserializeSeed<'de> for TrimStringSeed {
+    type Value = String;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        crate::string(de)
+    }
+}
+
+/// Seed that deserializes a trimmed string, returning error if it ends up empty.
+///
+/// Like [`TrimStringSeed`], the check happens inside the element's deserialization, so that errors
+/// carry the element's path and the string is still untrimmed when the error is built.
+pub(crate) struct NonEmptyTrimStringSeed {
+    pub(crate) index: usize,
+}
+
+impl<'de> de::DeserializeSeed<'de> for NonEmptyTrimStringSeed {
+    type Value = String;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        let mut val = String::deserialize(de)?;
+
+        if val.trim().is_empty() {
+            return Err(Error::Empty {
+                original: val,
+                index: Some(self.index),
+            }
+            .into_de_error());
+        }
+
+        trim_in_place(&mut val);
+        Ok(val)
+    }
+}
+
+/// Visitor that trims each string in a sequence in place and inserts it directly into `C`.
+pub(crate) struct TrimSeqVisitor<C> {
+    empty_items: EmptyItems,
+    at_least_one: bool,
+    collection: PhantomData<fn() -> C>,
+}
+
+impl<C> TrimSeqVisitor<C> {
+    pub(crate) fn new(empty_items: EmptyItems, at_least_one: bool) -> Self {
+        Self {
+            empty_items,
+            at_least_one,
+            collection: PhantomData,
+        }
+    }
+}
+
+impl<'de, C: StringCollection> de::Visitor<'de> for TrimSeqVisitor<C> {
+    type Value = C;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a sequence of strings")
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut collection = C::with_capacity(capacity(seq.size_hint()));
+        let mut idx = 0;
+
+        loop {
+            let item = match self.empty_items {
+                EmptyItems::Reject => {
+                    seq.next_element_seed(NonEmptyTrimStringSeed { index: idx })?
+                }
+                EmptyItems::Keep | EmptyItems::Skip => seq.next_element_seed(TrimStringSeed)?,
+            };
+
+            let Some(mut item) = item else {
+                break;
+            };
+
+            if self.empty_items == EmptyItems::Keep || !item.is_empty() {
+                // trimming in place keeps the original capacity, which the collection would
+                // otherwise hold on to for as long as it lives
+                item.shrink_to_fit();
+                collection.insert(item);
+            }
+
+            idx += 1;
+        }
+
+        if self.at_least_one && collection.is_empty() {
+            return Err(Error::NoItems.into_de_error());
+        }
+
+        Ok(collection)
+    }
+}
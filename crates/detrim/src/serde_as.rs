@@ -0,0 +1,221 @@
+//! Adapters for use with [`serde_with`]'s `#[serde_as]` attribute.
+//!
+//! Unlike the free functions in this crate, these marker types can be nested inside other types in
+//! a `serde_as` annotation, like `Option<Vec<Trim>>` or `BTreeMap<_, TrimNonEmpty>`, so that a
+//! separate function is not needed for every container shape.
+//!
+//! The types live in this module, rather than at the crate root, to avoid clashing with the
+//! [`Trim`](crate::Trim) trait.
+//!
+//! Requires the `serde_with` crate feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::collections::BTreeMap;
+//!
+//! use detrim::serde_as::{Trim, TrimNonEmpty};
+//! use serde_with::serde_as;
+//!
+//! #[serde_as]
+//! #[derive(Debug, serde::Deserialize)]
+//! struct Form {
+//!     #[serde_as(as = "Option<Vec<Trim>>")]
+//!     tags: Option<Vec<String>>,
+//!     #[serde_as(as = "BTreeMap<_, TrimNonEmpty>")]
+//!     labels: BTreeMap<String, String>,
+//! }
+//!
+//! let form = serde_json::from_str::<Form>(
+//!     r#"{ "tags": [" crab ", "rust"], "labels": { "team": " core " } }"#,
+//! )
+//! .unwrap();
+//! assert_eq!(form.tags.unwrap(), ["crab", "rust"]);
+//! assert_eq!(form.labels["team"], "core");
+//! ```
+
+use alloc::{borrow::Cow, string::String};
+
+use serde::{ser, Deserializer, Serializer};
+use serde_with::{DeserializeAs, SerializeAs};
+
+use crate::cow_str::CowStrVisitor;
+
+/// Trims strings during (de)serialization.
+///
+/// Implements `DeserializeAs` and `SerializeAs` for `String` and `Cow<str>`.
+#[derive(Debug)]
+pub enum Trim {}
+
+/// Trims strings during (de)serialization, returning error if they end up empty.
+///
+/// Implements `DeserializeAs` and `SerializeAs` for `String` and `Cow<str>`.
+#[derive(Debug)]
+pub enum TrimNonEmpty {}
+
+/// Trims CoW strings during deserialization, borrowing from the input where possible.
+///
+/// Implements `DeserializeAs` and `SerializeAs` for `Cow<str>`. The field must be marked with
+/// `#[serde(borrow)]` to borrow.
+#[derive(Debug)]
+pub enum TrimCow {}
+
+impl<'de> DeserializeAs<'de, String> for Trim {
+    fn deserialize_as<D: Deserializer<'de>>(de: D) -> Result<String, D::Error> {
+        crate::string(de)
+    }
+}
+
+impl<'de> DeserializeAs<'de, Cow<'de, str>> for Trim {
+    fn deserialize_as<D: Deserializer<'de>>(de: D) -> Result<Cow<'de, str>, D::Error> {
+        crate::cow_str(de)
+    }
+}
+
+impl SerializeAs<String> for Trim {
+    fn serialize_as<S: Serializer>(source: &String, ser: S) -> Result<S::Ok, S::Error> {
+        ser.serialize_str(source.trim())
+    }
+}
+
+impl SerializeAs<Cow<'_, str>> for Trim {
+    fn serialize_as<S: Serializer>(source: &Cow<'_, str>, ser: S) -> Result<S::Ok, S::Error> {
+        ser.serialize_str(source.trim())
+    }
+}
+
+impl<'de> DeserializeAs<'de, String> for TrimNonEmpty {
+    fn deserialize_as<D: Deserializer<'de>>(de: D) -> Result<String, D::Error> {
+        crate::string_non_empty(de)
+    }
+}
+
+impl<'de> DeserializeAs<'de, Cow<'de, str>> for TrimNonEmpty {
+    fn deserialize_as<D: Deserializer<'de>>(de: D) -> Result<Cow<'de, str>, D::Error> {
+        de.deserialize_str(CowStrVisitor { non_empty: true })
+    }
+}
+
+impl SerializeAs<String> for TrimNonEmpty {
+    fn serialize_as<S: Serializer>(source: &String, ser: S) -> Result<S::Ok, S::Error> {
+        crate::with::string_non_empty::serialize(source, ser)
+    }
+}
+
+impl SerializeAs<Cow<'_, str>> for TrimNonEmpty {
+    fn serialize_as<S: Serializer>(source: &Cow<'_, str>, ser: S) -> Result<S::Ok, S::Error> {
+        match source.trim() {
+            "" => Err(ser::Error::custom("expected non-empty string")),
+            val => ser.serialize_str(val),
+        }
+    }
+}
+
+impl<'de> DeserializeAs<'de, Cow<'de, str>> for TrimCow {
+    fn deserialize_as<D: Deserializer<'de>>(de: D) -> Result<Cow<'de, str>, D::Error> {
+        crate::cow_str(de)
+    }
+}
+
+impl SerializeAs<Cow<'_, str>> for TrimCow {
+    fn serialize_as<S: Serializer>(source: &Cow<'_, str>, ser: S) -> Result<S::Ok, S::Error> {
+        ser.serialize_str(source.trim())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{borrow::ToOwned as _, collections::BTreeMap, vec, vec::Vec};
+
+    use serde::{Deserialize, Serialize};
+    use serde_with::serde_as;
+
+    use super::*;
+
+    #[test]
+    fn trim() {
+        #[serde_as]
+        #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde_as(as = "Option<Vec<Trim>>")]
+            foo: Option<Vec<String>>,
+            #[serde_as(as = "BTreeMap<_, Trim>")]
+            bar: BTreeMap<String, String>,
+        }
+
+        let foo =
+            serde_json::from_str::<Foo>(r#"{ "foo": [" a", "b "], "bar": { " k ": " v " } }"#)
+                .unwrap();
+        assert_eq!(
+            foo.foo.as_deref(),
+            Some(&["a".to_owned(), "b".to_owned()][..])
+        );
+        assert_eq!(
+            foo.bar,
+            BTreeMap::from([(" k ".to_owned(), "v".to_owned())])
+        );
+
+        let foo = serde_json::from_str::<Foo>(r#"{ "foo": null, "bar": {} }"#).unwrap();
+        assert_eq!(foo.foo, None);
+
+        let foo = Foo {
+            foo: Some(vec![" a ".to_owned()]),
+            bar: BTreeMap::new(),
+        };
+        assert_eq!(
+            serde_json::to_string(&foo).unwrap(),
+            r#"{"foo":["a"],"bar":{}}"#,
+        );
+    }
+
+    #[test]
+    fn trim_non_empty() {
+        #[serde_as]
+        #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde_as(as = "Vec<TrimNonEmpty>")]
+            foo: Vec<String>,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": ["a", " "] }"#).unwrap_err();
+
+        let foo = serde_json::from_str::<Foo>(r#"{ "foo": [" a "] }"#).unwrap();
+        assert_eq!(foo.foo, ["a"]);
+
+        let foo = Foo {
+            foo: vec![" ".to_owned()],
+        };
+        serde_json::to_string(&foo).unwrap_err();
+    }
+
+    #[test]
+    fn trim_cow() {
+        #[serde_as]
+        #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+        struct Foo<'a> {
+            #[serde(borrow)]
+            #[serde_as(as = "Option<TrimCow>")]
+            foo: Option<Cow<'a, str>>,
+            #[serde(borrow)]
+            #[serde_as(as = "Vec<TrimNonEmpty>")]
+            bar: Vec<Cow<'a, str>>,
+        }
+
+        let source = br#"{ "foo": " a ", "bar": [" b", "c\\d "] }"#.to_vec();
+        let foo = serde_json::from_slice::<Foo<'_>>(&source).unwrap();
+        assert!(matches!(foo.foo, Some(Cow::Borrowed("a"))));
+        assert!(matches!(foo.bar[0], Cow::Borrowed("b")));
+        assert!(matches!(&foo.bar[1], Cow::Owned(val) if val == "c\\d"));
+
+        serde_json::from_slice::<Foo<'_>>(br#"{ "foo": null, "bar": [""] }"#).unwrap_err();
+
+        let foo = Foo {
+            foo: Some(Cow::Borrowed(" a ")),
+            bar: Vec::new(),
+        };
+        assert_eq!(
+            serde_json::to_string(&foo).unwrap(),
+            r#"{"foo":"a","bar":[]}"#,
+        );
+    }
+}
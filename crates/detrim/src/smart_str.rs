@@ -0,0 +1,287 @@
+use alloc::{boxed::Box, rc::Rc, str, sync::Arc, vec::Vec};
+use core::{fmt, marker::PhantomData};
+
+use serde::{de, Deserializer};
+
+use crate::{seq::VecSeedVisitor, string::trim};
+
+/// Visitor and seed that constructs `T` directly from a trimmed string slice.
+struct TrimInto<T>(PhantomData<fn() -> T>);
+
+impl<T> TrimInto<T> {
+    fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<'de, T: for<'a> From<&'a str>> de::Visitor<'de> for TrimInto<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a string")
+    }
+
+    fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+        Ok(T::from(trim(val)))
+    }
+
+    fn visit_bytes<E: de::Error>(self, val: &[u8]) -> Result<Self::Value, E> {
+        match str::from_utf8(val) {
+            Ok(val) => Ok(T::from(trim(val))),
+            Err(_) => Err(de::Error::invalid_value(de::Unexpected::Bytes(val), &self)),
+        }
+    }
+}
+
+impl<'de, T: for<'a> From<&'a str>> de::DeserializeSeed<'de> for TrimInto<T> {
+    type Value = T;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_str(self)
+    }
+}
+
+struct OptionTrimInto<T>(PhantomData<fn() -> T>);
+
+impl<'de, T: for<'a> From<&'a str>> de::Visitor<'de> for OptionTrimInto<T> {
+    type Value = Option<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("an optional string")
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_str(TrimInto::new()).map(Some)
+    }
+}
+
+macro_rules! impl_trim_into {
+    ($ty:ty, $name:literal, $single:ident, $option:ident, $vec:ident) => {
+        #[doc = concat!("Trims a string into ", $name, " during deserialization.")]
+        pub fn $single<'de, D: Deserializer<'de>>(de: D) -> Result<$ty, D::Error> {
+            de.deserialize_str(TrimInto::new())
+        }
+
+        #[doc = concat!("Trims an optional string into ", $name, " during deserialization.")]
+        pub fn $option<'de, D: Deserializer<'de>>(de: D) -> Result<Option<$ty>, D::Error> {
+            de.deserialize_option(OptionTrimInto(PhantomData))
+        }
+
+        #[doc = concat!("Trims list of strings into ", $name, "s during deserialization.")]
+        pub fn $vec<'de, D: Deserializer<'de>>(de: D) -> Result<Vec<$ty>, D::Error> {
+            de.deserialize_seq(VecSeedVisitor::new("a sequence of strings", |_| {
+                TrimInto::new()
+            }))
+        }
+    };
+}
+
+impl_trim_into!(
+    Box<str>,
+    "a `Box<str>`",
+    box_str,
+    option_box_str,
+    vec_box_str
+);
+impl_trim_into!(
+    Arc<str>,
+    "an `Arc<str>`",
+    arc_str,
+    option_arc_str,
+    vec_arc_str
+);
+impl_trim_into!(Rc<str>, "an `Rc<str>`", rc_str, option_rc_str, vec_rc_str);
+
+#[cfg(feature = "compact_str")]
+impl_trim_into!(
+    compact_str::CompactString,
+    "a `CompactString`",
+    compact_string,
+    option_compact_string,
+    vec_compact_string
+);
+
+#[cfg(feature = "smol_str")]
+impl_trim_into!(
+    smol_str::SmolStr,
+    "a `SmolStr`",
+    smol_str,
+    option_smol_str,
+    vec_smol_str
+);
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[test]
+    fn box_str() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::box_str")]
+            foo: Box<str>,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": 1 }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": null }"#).unwrap_err();
+
+        assert_eq!(
+            &*serde_json::from_str::<Foo>(r#"{ "foo": " " }"#)
+                .unwrap()
+                .foo,
+            "",
+        );
+        assert_eq!(
+            &*serde_json::from_str::<Foo>(r#"{ "foo": "  bar  " }"#)
+                .unwrap()
+                .foo,
+            "bar",
+        );
+        assert_eq!(
+            &*serde_json::from_str::<Foo>(r#"{ "foo": " b\\ar " }"#)
+                .unwrap()
+                .foo,
+            "b\\ar",
+        );
+    }
+
+    #[test]
+    fn arc_str() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::arc_str")]
+            foo: Arc<str>,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": 1 }"#).unwrap_err();
+
+        assert_eq!(
+            &*serde_json::from_str::<Foo>(r#"{ "foo": "  bar  " }"#)
+                .unwrap()
+                .foo,
+            "bar",
+        );
+    }
+
+    #[test]
+    fn rc_str() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::rc_str")]
+            foo: Rc<str>,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": 1 }"#).unwrap_err();
+
+        assert_eq!(
+            &*serde_json::from_str::<Foo>(r#"{ "foo": "  bar  " }"#)
+                .unwrap()
+                .foo,
+            "bar",
+        );
+    }
+
+    #[test]
+    fn option_arc_str() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::option_arc_str")]
+            foo: Option<Arc<str>>,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": 1 }"#).unwrap_err();
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": null }"#)
+                .unwrap()
+                .foo,
+            None,
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": "  bar  " }"#)
+                .unwrap()
+                .foo
+                .as_deref(),
+            Some("bar"),
+        );
+    }
+
+    #[test]
+    fn vec_arc_str() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::vec_arc_str")]
+            foo: Vec<Arc<str>>,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": 1 }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": [1] }"#).unwrap_err();
+
+        assert!(serde_json::from_str::<Foo>(r#"{ "foo": [] }"#)
+            .unwrap()
+            .foo
+            .is_empty());
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": [" bar", "baz "] }"#)
+                .unwrap()
+                .foo,
+            [Arc::from("bar"), Arc::from("baz")],
+        );
+    }
+
+    #[cfg(feature = "compact_str")]
+    #[test]
+    fn compact_string() {
+        use compact_str::CompactString;
+
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::compact_string")]
+            foo: CompactString,
+            #[serde(deserialize_with = "super::option_compact_string")]
+            bar: Option<CompactString>,
+            #[serde(deserialize_with = "super::vec_compact_string")]
+            baz: Vec<CompactString>,
+        }
+
+        let json =
+            serde_json::from_str::<Foo>(r#"{ "foo": " foo ", "bar": " bar ", "baz": [" baz "] }"#)
+                .unwrap();
+        assert_eq!(json.foo, "foo");
+        assert_eq!(json.bar.as_deref(), Some("bar"));
+        assert_eq!(json.baz, ["baz"]);
+    }
+
+    #[cfg(feature = "smol_str")]
+    #[test]
+    fn smol_str() {
+        use smol_str::SmolStr;
+
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::smol_str")]
+            foo: SmolStr,
+            #[serde(deserialize_with = "super::option_smol_str")]
+            bar: Option<SmolStr>,
+            #[serde(deserialize_with = "super::vec_smol_str")]
+            baz: Vec<SmolStr>,
+        }
+
+        let json =
+            serde_json::from_str::<Foo>(r#"{ "foo": " foo ", "bar": " bar ", "baz": [" baz "] }"#)
+                .unwrap();
+        assert_eq!(json.foo, "foo");
+        assert_eq!(json.bar.as_deref(), Some("bar"));
+        assert_eq!(json.baz, ["baz"]);
+    }
+}
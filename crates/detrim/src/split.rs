@@ -0,0 +1,263 @@
+//! Trimming deserializers for delimiter-separated lists.
+//!
+//! Each function accepts either a single string, which is split on the `DELIM` character, or an
+//! actual sequence of strings. Every part is trimmed. A string that is empty after trimming
+//! produces an empty collection.
+//!
+//! Since the input shape is detected at runtime, these functions are only supported by
+//! self-describing formats.
+//!
+//! # Examples
+//!
+//! ```
+//! #[derive(Debug, serde::Deserialize)]
+//! struct Config {
+//!     #[serde(deserialize_with = "detrim::split::vec_string_skip_empty::<',', _>")]
+//!     roles: Vec<String>,
+//! }
+//!
+//! let config = serde_json::from_str::<Config>(r#"{ "roles": " admin, ops ,  " }"#).unwrap();
+//! assert_eq!(config.roles, ["admin", "ops"]);
+//!
+//! let config = serde_json::from_str::<Config>(r#"{ "roles": [" admin", "ops "] }"#).unwrap();
+//! assert_eq!(config.roles, ["admin", "ops"]);
+//! ```
+
+use alloc::{collections::BTreeSet, string::String, vec::Vec};
+use core::{fmt, marker::PhantomData};
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+
+use serde::{de, Deserializer};
+
+use crate::{
+    seq::{EmptyItems, StringCollection, TrimSeqVisitor},
+    string::trim,
+};
+
+/// Visitor that splits a string on `DELIM`, or reads a sequence, trimming each part.
+struct SplitVisitor<C, const DELIM: char> {
+    empty_items: EmptyItems,
+    collection: PhantomData<fn() -> C>,
+}
+
+impl<C, const DELIM: char> SplitVisitor<C, DELIM> {
+    fn new(empty_items: EmptyItems) -> Self {
+        Self {
+            empty_items,
+            collection: PhantomData,
+        }
+    }
+}
+
+impl<'de, C: StringCollection, const DELIM: char> de::Visitor<'de> for SplitVisitor<C, DELIM> {
+    type Value = C;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "a {DELIM:?}-separated string or a sequence of strings",
+        )
+    }
+
+    fn visit_str<E: de::Error>(self, val: &str) -> Result<Self::Value, E> {
+        let val = trim(val);
+        let mut collection = C::with_capacity(0);
+
+        if val.is_empty() {
+            return Ok(collection);
+        }
+
+        for part in val.split(DELIM).map(trim) {
+            if part.is_empty() && self.empty_items == EmptyItems::Skip {
+                continue;
+            }
+
+            collection.insert(String::from(part));
+        }
+
+        Ok(collection)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        TrimSeqVisitor::new(self.empty_items, false).visit_seq(seq)
+    }
+}
+
+/// Splits a string on `DELIM` during deserialization, trimming each part.
+///
+/// See [module docs](self) for more.
+pub fn vec_string<'de, const DELIM: char, D: Deserializer<'de>>(
+    de: D,
+) -> Result<Vec<String>, D::Error> {
+    de.deserialize_any(SplitVisitor::<_, DELIM>::new(EmptyItems::Keep))
+}
+
+/// Splits a string on `DELIM` during deserialization, trimming each part and removing any that end
+/// up empty.
+///
+/// See [module docs](self) for more.
+pub fn vec_string_skip_empty<'de, const DELIM: char, D: Deserializer<'de>>(
+    de: D,
+) -> Result<Vec<String>, D::Error> {
+    de.deserialize_any(SplitVisitor::<_, DELIM>::new(EmptyItems::Skip))
+}
+
+/// Splits a string on `DELIM` into a set during deserialization, trimming each part.
+///
+/// Parts are deduplicated _after_ being trimmed. See [module docs](self) for more.
+#[cfg(feature = "std")]
+pub fn hashset_string<'de, const DELIM: char, D: Deserializer<'de>>(
+    de: D,
+) -> Result<HashSet<String>, D::Error> {
+    de.deserialize_any(SplitVisitor::<_, DELIM>::new(EmptyItems::Keep))
+}
+
+/// Splits a string on `DELIM` into a set during deserialization, trimming each part and removing
+/// any that end up empty.
+///
+/// Parts are deduplicated _after_ being trimmed. See [module docs](self) for more.
+#[cfg(feature = "std")]
+pub fn hashset_string_skip_empty<'de, const DELIM: char, D: Deserializer<'de>>(
+    de: D,
+) -> Result<HashSet<String>, D::Error> {
+    de.deserialize_any(SplitVisitor::<_, DELIM>::new(EmptyItems::Skip))
+}
+
+/// Splits a string on `DELIM` into an ordered set during deserialization, trimming each part.
+///
+/// Parts are deduplicated _after_ being trimmed. See [module docs](self) for more.
+pub fn btreeset_string<'de, const DELIM: char, D: Deserializer<'de>>(
+    de: D,
+) -> Result<BTreeSet<String>, D::Error> {
+    de.deserialize_any(SplitVisitor::<_, DELIM>::new(EmptyItems::Keep))
+}
+
+/// Splits a string on `DELIM` into an ordered set during deserialization, trimming each part and
+/// removing any that end up empty.
+///
+/// Parts are deduplicated _after_ being trimmed. See [module docs](self) for more.
+pub fn btreeset_string_skip_empty<'de, const DELIM: char, D: Deserializer<'de>>(
+    de: D,
+) -> Result<BTreeSet<String>, D::Error> {
+    de.deserialize_any(SplitVisitor::<_, DELIM>::new(EmptyItems::Skip))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[test]
+    fn vec_string() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::vec_string::<',', _>")]
+            foo: Vec<String>,
+        }
+
+        impl Foo {
+            fn new(foo: impl IntoIterator<Item = impl Into<String>>) -> Self {
+                Self {
+                    foo: foo.into_iter().map(Into::into).collect(),
+                }
+            }
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": 1 }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": [1] }"#).unwrap_err();
+
+        assert_eq!(
+            Foo::new([""; 0]),
+            serde_json::from_str(r#"{ "foo": "" }"#).unwrap(),
+        );
+        assert_eq!(
+            Foo::new([""; 0]),
+            serde_json::from_str(r#"{ "foo": "  " }"#).unwrap(),
+        );
+        assert_eq!(
+            Foo::new(["bar"]),
+            serde_json::from_str(r#"{ "foo": " bar " }"#).unwrap(),
+        );
+        assert_eq!(
+            Foo::new(["admin", "ops", ""]),
+            serde_json::from_str(r#"{ "foo": " admin, ops ,  " }"#).unwrap(),
+        );
+        assert_eq!(
+            Foo::new(["admin", "ops"]),
+            serde_json::from_str(r#"{ "foo": [" admin", "ops "] }"#).unwrap(),
+        );
+        assert_eq!(
+            Foo::new(["a, b"]),
+            serde_json::from_str(r#"{ "foo": [" a, b "] }"#).unwrap(),
+        );
+    }
+
+    #[test]
+    fn vec_string_skip_empty() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::vec_string_skip_empty::<';', _>")]
+            foo: Vec<String>,
+        }
+
+        impl Foo {
+            fn new(foo: impl IntoIterator<Item = impl Into<String>>) -> Self {
+                Self {
+                    foo: foo.into_iter().map(Into::into).collect(),
+                }
+            }
+        }
+
+        assert_eq!(
+            Foo::new([""; 0]),
+            serde_json::from_str(r#"{ "foo": " ; ;" }"#).unwrap(),
+        );
+        assert_eq!(
+            Foo::new(["admin", "ops, dev"]),
+            serde_json::from_str(r#"{ "foo": " admin;; ops, dev ;  " }"#).unwrap(),
+        );
+        assert_eq!(
+            Foo::new(["admin"]),
+            serde_json::from_str(r#"{ "foo": [" admin", " "] }"#).unwrap(),
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hashset_string() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::hashset_string::<',', _>")]
+            foo: HashSet<String>,
+            #[serde(deserialize_with = "super::hashset_string_skip_empty::<',', _>")]
+            bar: HashSet<String>,
+        }
+
+        let json =
+            serde_json::from_str::<Foo>(r#"{ "foo": "a, a ,b,", "bar": ["a", " a ", " "] }"#)
+                .unwrap();
+        assert_eq!(
+            json.foo,
+            HashSet::from(["a".to_owned(), "b".to_owned(), "".to_owned()]),
+        );
+        assert_eq!(json.bar, HashSet::from(["a".to_owned()]));
+    }
+
+    #[test]
+    fn btreeset_string() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::btreeset_string::<' ', _>")]
+            foo: BTreeSet<String>,
+            #[serde(deserialize_with = "super::btreeset_string_skip_empty::<'|', _>")]
+            bar: BTreeSet<String>,
+        }
+
+        let json =
+            serde_json::from_str::<Foo>(r#"{ "foo": " b a  b ", "bar": "b| a |b||" }"#).unwrap();
+        assert!(json.foo.iter().eq(["", "a", "b"]));
+        assert!(json.bar.iter().eq(["a", "b"]));
+    }
+}
@@ -0,0 +1,222 @@
+//! Deserializers that reject surrounding whitespace instead of trimming it.
+//!
+//! Whitespace is defined the same way as for the trimming functions in this crate. Errors say
+//! whether the leading or trailing side had whitespace and show the offending characters.
+//!
+//! # Examples
+//!
+//! ```
+//! #[derive(Debug, serde::Deserialize)]
+//! struct Request {
+//!     #[serde(deserialize_with = "detrim::strict::string")]
+//!     id: String,
+//! }
+//!
+//! let req = serde_json::from_str::<Request>(r#"{ "id": "abc" }"#).unwrap();
+//! assert_eq!(req.id, "abc");
+//!
+//! let err = serde_json::from_str::<Request>(r#"{ "id": " abc" }"#).unwrap_err();
+//! assert_eq!(
+//!     err.to_string(),
+//!     "invalid value: string \" abc\", expected a string without leading or trailing whitespace \
+//!      (found leading whitespace \" \") at line 1 column 16",
+//! );
+//! ```
+
+use alloc::{borrow::ToOwned as _, string::String, vec::Vec};
+
+use serde::{de, Deserialize as _, Deserializer};
+
+use crate::{error::Error, seq::VecSeedVisitor, string::is_untrimmed};
+
+pub(crate) const EXPECTED: &str = "a string without leading or trailing whitespace";
+
+/// Returns an error describing any leading or trailing whitespace in `val`.
+fn check<E: de::Error>(val: &str, index: Option<usize>) -> Result<(), E> {
+    if !is_untrimmed(val) {
+        return Ok(());
+    }
+
+    Err(Error::Untrimmed {
+        original: val.to_owned(),
+        index,
+    }
+    .into_de_error())
+}
+
+/// Deserializes a string slice, returning error if it has leading or trailing whitespace.
+pub fn str<'a, 'de: 'a, D: Deserializer<'de>>(de: D) -> Result<&'a str, D::Error> {
+    let val = <&'a str>::deserialize(de)?;
+    check(val, None)?;
+    Ok(val)
+}
+
+/// Deserializes a string, returning error if it has leading or trailing whitespace.
+pub fn string<'de, D: Deserializer<'de>>(de: D) -> Result<String, D::Error> {
+    let val = String::deserialize(de)?;
+    check(&val, None)?;
+    Ok(val)
+}
+
+/// Deserializes an optional string, returning error if it has leading or trailing whitespace.
+pub fn option_string<'de, D: Deserializer<'de>>(de: D) -> Result<Option<String>, D::Error> {
+    let val = Option::<String>::deserialize(de)?;
+
+    if let Some(val) = &val {
+        check(val, None)?;
+    }
+
+    Ok(val)
+}
+
+/// Deserializes a sequence of strings, returning error if any have leading or trailing whitespace.
+///
+/// The error includes the index of the first offending string.
+pub fn vec_string<'de, D: Deserializer<'de>>(de: D) -> Result<Vec<String>, D::Error> {
+    /// Seed that deserializes a sequence item, checking it inside its own deserialization so that
+    /// errors carry the item's path.
+    struct StrictSeed {
+        index: usize,
+    }
+
+    impl<'de> de::DeserializeSeed<'de> for StrictSeed {
+        type Value = String;
+
+        fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+            let val = String::deserialize(de)?;
+            check(&val, Some(self.index))?;
+            Ok(val)
+        }
+    }
+
+    de.deserialize_seq(VecSeedVisitor::new(
+        "a sequence of strings without leading or trailing whitespace",
+        |index| StrictSeed { index },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString as _;
+
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[test]
+    fn string() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::string")]
+            foo: String,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": 1 }"#).unwrap_err();
+
+        let err = serde_json::from_str::<Foo>(r#"{ "foo": " \tbar" }"#).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid value: string \" \\tbar\", expected a string without leading or trailing \
+             whitespace (found leading whitespace \" \\t\") at line 1 column 19",
+        );
+
+        let err = serde_json::from_str::<Foo>(r#"{ "foo": "bar\n" }"#).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid value: string \"bar\\n\", expected a string without leading or trailing \
+             whitespace (found trailing whitespace \"\\n\") at line 1 column 18",
+        );
+
+        let err = serde_json::from_str::<Foo>(r#"{ "foo": "\u00a0bar " }"#).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid value: string \"\\u{a0}bar \", expected a string without leading or trailing \
+             whitespace (found leading whitespace \"\\u{a0}\" and trailing whitespace \" \") \
+             at line 1 column 23",
+        );
+
+        let err = serde_json::from_str::<Foo>(r#"{ "foo": "  " }"#).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid value: string \"  \", expected a string without leading or trailing \
+             whitespace (whitespace-only string) at line 1 column 15",
+        );
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": "" }"#).unwrap().foo,
+            "",
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": "bar baz" }"#)
+                .unwrap()
+                .foo,
+            "bar baz",
+        );
+    }
+
+    #[test]
+    fn str() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo<'a> {
+            #[serde(borrow, deserialize_with = "super::str")]
+            foo: &'a str,
+        }
+
+        serde_json::from_str::<Foo<'_>>(r#"{ "foo": "bar " }"#).unwrap_err();
+
+        assert_eq!(
+            serde_json::from_str::<Foo<'_>>(r#"{ "foo": "bar" }"#)
+                .unwrap()
+                .foo,
+            "bar",
+        );
+    }
+
+    #[test]
+    fn option_string() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::option_string")]
+            foo: Option<String>,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": " bar" }"#).unwrap_err();
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": null }"#)
+                .unwrap()
+                .foo,
+            None,
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": "bar" }"#)
+                .unwrap()
+                .foo
+                .as_deref(),
+            Some("bar"),
+        );
+    }
+
+    #[test]
+    fn vec_string() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::vec_string")]
+            foo: Vec<String>,
+        }
+
+        let err = serde_json::from_str::<Foo>(r#"{ "foo": ["bar", "baz "] }"#).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid value: string \"baz \", expected a string without leading or trailing \
+             whitespace at index 1 (found trailing whitespace \" \") at line 1 column 24",
+        );
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": ["bar", "baz"] }"#)
+                .unwrap()
+                .foo,
+            ["bar", "baz"],
+        );
+    }
+}
@@ -1,21 +1,75 @@
-use alloc::{borrow::ToOwned as _, string::String};
+use alloc::string::String;
 
 use serde::{Deserialize as _, Deserializer};
 
 /// Trims a string slice during deserialization.
 pub fn str<'a, 'de: 'a, D: Deserializer<'de>>(de: D) -> Result<&'a str, D::Error> {
-    <&'a str>::deserialize(de).map(|val| val.trim())
+    <&'a str>::deserialize(de).map(trim)
+}
+
+/// Trims an optional string slice during deserialization.
+pub fn option_str<'a, 'de: 'a, D: Deserializer<'de>>(de: D) -> Result<Option<&'a str>, D::Error> {
+    <Option<&'a str>>::deserialize(de).map(|val| val.map(trim))
 }
 
 /// Trims a string during deserialization.
 pub fn string<'de, D: Deserializer<'de>>(de: D) -> Result<String, D::Error> {
-    String::deserialize(de).map(|val| val.trim().to_owned())
+    let mut val = String::deserialize(de)?;
+    trim_in_place(&mut val);
+    Ok(val)
 }
 
 /// Trims an optional string during deserialization.
 pub fn option_string<'de, D: Deserializer<'de>>(de: D) -> Result<Option<String>, D::Error> {
-    let val = Option::<String>::deserialize(de)?;
-    Ok(val.map(|val| val.trim().to_owned()))
+    let mut val = Option::<String>::deserialize(de)?;
+
+    if let Some(val) = &mut val {
+        trim_in_place(val);
+    }
+
+    Ok(val)
+}
+
+/// Returns true if `c` is whitespace removed by the trimming functions in this crate.
+///
+/// This is the definition used by [`str::trim`]: characters with the Unicode `White_Space`
+/// property.
+pub(crate) fn is_trim_whitespace(c: char) -> bool {
+    c.is_whitespace()
+}
+
+/// Returns true if `val` has leading or trailing whitespace.
+///
+/// Unlike [`trim`], nothing is reported to the active observer.
+pub(crate) fn is_untrimmed(val: &str) -> bool {
+    val.starts_with(is_trim_whitespace) || val.ends_with(is_trim_whitespace)
+}
+
+/// Trims a string slice, reporting any removed whitespace to the active observer.
+pub(crate) fn trim(val: &str) -> &str {
+    let trimmed = val.trim_matches(is_trim_whitespace);
+
+    #[cfg(feature = "observe")]
+    crate::observe::report_trimmed(val, trimmed);
+
+    trimmed
+}
+
+/// Trims a string in place, without re-allocating.
+///
+/// Removed whitespace is reported to the active observer.
+pub(crate) fn trim_in_place(val: &mut String) {
+    #[cfg(feature = "observe")]
+    let len = val.len();
+
+    let end = val.trim_end_matches(is_trim_whitespace).len();
+    val.truncate(end);
+
+    let start = val.len() - val.trim_start_matches(is_trim_whitespace).len();
+    val.drain(..start);
+
+    #[cfg(feature = "observe")]
+    crate::observe::report(start, len - end);
 }
 
 #[cfg(test)]
@@ -71,6 +125,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn option_str() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo<'a> {
+            #[serde(borrow, deserialize_with = "super::option_str")]
+            foo: Option<&'a str>,
+        }
+
+        serde_json::from_str::<Foo<'static>>(r#"{ "foo": 1 }"#).unwrap_err();
+
+        // escape sequences cannot be borrowed
+        serde_json::from_str::<Foo<'static>>(r#"{ "foo": " b\\ar " }"#).unwrap_err();
+
+        assert_eq!(
+            serde_json::from_str::<Foo<'_>>(r#"{ "foo": null }"#)
+                .unwrap()
+                .foo,
+            None,
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo<'_>>(r#"{ "foo": " " }"#)
+                .unwrap()
+                .foo,
+            Some(""),
+        );
+        assert_eq!(
+            serde_json::from_str::<Foo<'_>>(r#"{ "foo": "  bar  " }"#)
+                .unwrap()
+                .foo,
+            Some("bar"),
+        );
+    }
+
     #[test]
     fn string() {
         #[derive(Debug, Deserialize, PartialEq, Eq)]
@@ -118,6 +205,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn trim_in_place() {
+        let mut val = String::from("  bar \n");
+        let capacity = val.capacity();
+        super::trim_in_place(&mut val);
+        assert_eq!(val, "bar");
+        assert_eq!(val.capacity(), capacity);
+
+        let mut val = String::from("   ");
+        super::trim_in_place(&mut val);
+        assert_eq!(val, "");
+    }
+
+    #[test]
+    fn is_untrimmed() {
+        // ideographic space is trimmed, zero width space and BOM are not
+        for val in [
+            "bar",
+            "\u{3000}bar",
+            "bar\u{85}",
+            "\u{200b}bar",
+            "bar\u{feff}",
+            "",
+        ] {
+            assert_eq!(super::is_untrimmed(val), trim(val) != val, "{val:?}");
+        }
+    }
+
     #[test]
     fn option_string() {
         #[derive(Debug, Deserialize, PartialEq, Eq)]
@@ -1,30 +1,37 @@
 use alloc::{borrow::ToOwned, string::String};
 
-use serde::{de, Deserialize as _, Deserializer};
+use serde::{Deserialize as _, Deserializer};
+
+use crate::{error::Error, string::trim};
 
 /// Trims string during deserialization, returning error if it ends up empty.
+///
+/// The error includes the original string.
 pub fn string_non_empty<'a, D: Deserializer<'a>>(de: D) -> Result<String, D::Error> {
-    match String::deserialize(de) {
-        Ok(val) if val.trim().is_empty() => Err(de::Error::invalid_value(
-            de::Unexpected::Other("empty string"),
-            &"non-empty string",
-        )),
-        Ok(val) => Ok(val.trim().to_owned()),
-        Err(err) => Err(err),
+    let val = String::deserialize(de)?;
+
+    match trim(&val) {
+        "" => Err(Error::Empty {
+            original: val,
+            index: None,
+        }
+        .into_de_error()),
+        val => Ok(val.to_owned()),
     }
 }
 
 /// Trims string during deserialization, returning `None` if it ends up empty.
 pub fn option_string_non_empty<'a, D: Deserializer<'a>>(de: D) -> Result<Option<String>, D::Error> {
-    match Option::<String>::deserialize(de)? {
-        None => Ok(None),
-        Some(val) if val.trim().is_empty() => Ok(None),
-        Some(val) => Ok(Some(val.trim().to_owned())),
+    match Option::<String>::deserialize(de)?.as_deref().map(trim) {
+        None | Some("") => Ok(None),
+        Some(val) => Ok(Some(val.to_owned())),
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use alloc::string::ToString as _;
+
     use serde::Deserialize;
 
     use super::*;
@@ -44,7 +51,10 @@ mod tests {
         }
 
         serde_json::from_str::<Foo>(r#"{ "foo": "" }"#).unwrap_err();
-        serde_json::from_str::<Foo>(r#"{ "foo": "  " }"#).unwrap_err();
+        let err = serde_json::from_str::<Foo>(r#"{ "foo": "  " }"#).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with(r#"invalid value: string "  ", expected non-empty string"#));
         serde_json::from_str::<Foo>(r#"{ "foo": null }"#).unwrap_err();
 
         assert_eq!(
@@ -0,0 +1,221 @@
+use alloc::{
+    borrow::Cow,
+    boxed::Box,
+    collections::{BTreeMap, VecDeque},
+    string::String,
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use crate::{string, NonEmptyString, TrimmedString};
+
+/// Types that can trim the strings they contain, in place.
+///
+/// Uses the same trimming semantics as the deserializers in this crate: leading and trailing
+/// whitespace, as defined by [`str::trim()`], is removed.
+///
+/// Collections and wrappers trim each of their items. Maps trim their values but not their keys,
+/// since modifying keys could change their order or cause collisions. Scalars like numbers and
+/// `bool` are implemented as no-ops so that structs mixing string and non-string fields can derive
+/// this trait.
+///
+/// A derive macro is available behind the `derive` crate feature. It trims every field, except
+/// those marked with `#[trim(skip)]`.
+///
+/// # Examples
+///
+/// ```
+/// use detrim::Trim as _;
+///
+/// let mut names = vec![Some(String::from(" ferris ")), None];
+/// names.trim_in_place();
+/// assert_eq!(names, [Some(String::from("ferris")), None]);
+/// ```
+pub trait Trim {
+    /// Trims all strings contained in `self`.
+    fn trim_in_place(&mut self);
+}
+
+impl Trim for String {
+    fn trim_in_place(&mut self) {
+        string::trim_in_place(self);
+    }
+}
+
+impl Trim for &str {
+    fn trim_in_place(&mut self) {
+        *self = string::trim(self);
+    }
+}
+
+impl Trim for Cow<'_, str> {
+    fn trim_in_place(&mut self) {
+        match self {
+            Cow::Borrowed(val) => val.trim_in_place(),
+            Cow::Owned(val) => val.trim_in_place(),
+        }
+    }
+}
+
+impl<T: Trim> Trim for Option<T> {
+    fn trim_in_place(&mut self) {
+        if let Some(val) = self {
+            val.trim_in_place();
+        }
+    }
+}
+
+impl<T: Trim + ?Sized> Trim for Box<T> {
+    fn trim_in_place(&mut self) {
+        (**self).trim_in_place();
+    }
+}
+
+impl<T: Trim> Trim for [T] {
+    fn trim_in_place(&mut self) {
+        for item in self {
+            item.trim_in_place();
+        }
+    }
+}
+
+impl<T: Trim, const N: usize> Trim for [T; N] {
+    fn trim_in_place(&mut self) {
+        self.as_mut_slice().trim_in_place();
+    }
+}
+
+impl<T: Trim> Trim for Vec<T> {
+    fn trim_in_place(&mut self) {
+        self.as_mut_slice().trim_in_place();
+    }
+}
+
+impl<T: Trim> Trim for VecDeque<T> {
+    fn trim_in_place(&mut self) {
+        for item in self {
+            item.trim_in_place();
+        }
+    }
+}
+
+impl<K, V: Trim> Trim for BTreeMap<K, V> {
+    fn trim_in_place(&mut self) {
+        for val in self.values_mut() {
+            val.trim_in_place();
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K, V: Trim, S> Trim for HashMap<K, V, S> {
+    fn trim_in_place(&mut self) {
+        for val in self.values_mut() {
+            val.trim_in_place();
+        }
+    }
+}
+
+macro_rules! impl_trim_tuple {
+    ($($ty:ident $idx:tt),+) => {
+        impl<$($ty: Trim),+> Trim for ($($ty,)+) {
+            fn trim_in_place(&mut self) {
+                $(self.$idx.trim_in_place();)+
+            }
+        }
+    };
+}
+
+impl_trim_tuple!(A 0);
+impl_trim_tuple!(A 0, B 1);
+impl_trim_tuple!(A 0, B 1, C 2);
+impl_trim_tuple!(A 0, B 1, C 2, D 3);
+impl_trim_tuple!(A 0, B 1, C 2, D 3, E 4);
+impl_trim_tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_trim_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_trim_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+impl_trim_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+impl_trim_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+impl_trim_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+impl_trim_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
+
+macro_rules! impl_trim_noop {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Trim for $ty {
+                fn trim_in_place(&mut self) {}
+            }
+        )*
+    };
+}
+
+impl_trim_noop! {
+    (),
+    bool,
+    char,
+    i8, i16, i32, i64, i128, isize,
+    u8, u16, u32, u64, u128, usize,
+    f32, f64,
+    // already trimmed on construction
+    TrimmedString,
+    NonEmptyString,
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{borrow::ToOwned as _, vec};
+
+    use super::*;
+
+    #[test]
+    fn strings() {
+        let mut val = String::from(" \tfoo bar\n");
+        val.trim_in_place();
+        assert_eq!(val, "foo bar");
+
+        let mut val = " foo ";
+        val.trim_in_place();
+        assert_eq!(val, "foo");
+
+        let mut val = Cow::Borrowed(" foo ");
+        val.trim_in_place();
+        assert!(matches!(val, Cow::Borrowed("foo")));
+
+        let mut val = Cow::<str>::Owned(" foo ".to_owned());
+        val.trim_in_place();
+        assert!(matches!(val, Cow::Owned(ref val) if val == "foo"));
+    }
+
+    #[test]
+    fn containers() {
+        let mut val = (
+            Some(Box::new(" foo ".to_owned())),
+            vec![" bar", "baz "],
+            [Some(" qux "), None],
+            1_u32,
+        );
+        val.trim_in_place();
+        assert_eq!(
+            val,
+            (
+                Some(Box::new("foo".to_owned())),
+                vec!["bar", "baz"],
+                [Some("qux"), None],
+                1,
+            ),
+        );
+
+        let mut val = BTreeMap::from([(" a ", " 1 ".to_owned())]);
+        val.trim_in_place();
+        assert_eq!(val, BTreeMap::from([(" a ", "1".to_owned())]));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hashmap() {
+        let mut val = HashMap::from([(" a ", vec![" 1 ".to_owned()])]);
+        val.trim_in_place();
+        assert_eq!(val, HashMap::from([(" a ", vec!["1".to_owned()])]));
+    }
+}
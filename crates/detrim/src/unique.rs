@@ -0,0 +1,259 @@
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+};
+use core::{fmt, marker::PhantomData};
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+
+use serde::{de, Deserializer};
+
+use crate::{
+    error::Error,
+    seq::{capacity, TrimStringSeed},
+};
+
+/// Set of strings that records where each item was first seen while being built.
+trait UniqueStringSet: Sized {
+    type Builder;
+
+    /// Creates an empty builder with space for at least `capacity` items.
+    fn builder(capacity: usize) -> Self::Builder;
+
+    /// Inserts item found at `idx`.
+    ///
+    /// If an equal item was already added, returns the item along with the index of the first.
+    fn insert(builder: &mut Self::Builder, item: String, idx: usize)
+        -> Result<(), (String, usize)>;
+
+    /// Converts builder into the final set.
+    fn finish(builder: Self::Builder) -> Self;
+}
+
+impl UniqueStringSet for BTreeSet<String> {
+    type Builder = BTreeMap<String, usize>;
+
+    fn builder(_capacity: usize) -> Self::Builder {
+        BTreeMap::new()
+    }
+
+    fn insert(
+        builder: &mut Self::Builder,
+        item: String,
+        idx: usize,
+    ) -> Result<(), (String, usize)> {
+        match builder.get(&item) {
+            Some(&first) => Err((item, first)),
+            None => {
+                builder.insert(item, idx);
+                Ok(())
+            }
+        }
+    }
+
+    fn finish(builder: Self::Builder) -> Self {
+        builder.into_keys().collect()
+    }
+}
+
+#[cfg(feature = "std")]
+impl UniqueStringSet for HashSet<String> {
+    type Builder = HashMap<String, usize>;
+
+    fn builder(capacity: usize) -> Self::Builder {
+        HashMap::with_capacity(capacity)
+    }
+
+    fn insert(
+        builder: &mut Self::Builder,
+        item: String,
+        idx: usize,
+    ) -> Result<(), (String, usize)> {
+        match builder.get(&item) {
+            Some(&first) => Err((item, first)),
+            None => {
+                builder.insert(item, idx);
+                Ok(())
+            }
+        }
+    }
+
+    fn finish(builder: Self::Builder) -> Self {
+        builder.into_keys().collect()
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl UniqueStringSet for indexmap::IndexSet<String> {
+    type Builder = Self;
+
+    fn builder(capacity: usize) -> Self::Builder {
+        indexmap::IndexSet::with_capacity(capacity)
+    }
+
+    fn insert(
+        builder: &mut Self::Builder,
+        item: String,
+        _idx: usize,
+    ) -> Result<(), (String, usize)> {
+        // items are only ever appended so set indices match sequence indices
+        match builder.get_index_of(&item) {
+            Some(first) => Err((item, first)),
+            None => {
+                builder.insert(item);
+                Ok(())
+            }
+        }
+    }
+
+    fn finish(builder: Self::Builder) -> Self {
+        builder
+    }
+}
+
+/// Visitor that trims each string in a sequence, returning an error on the first duplicate.
+struct UniqueSeqVisitor<C>(PhantomData<fn() -> C>);
+
+impl<'de, C: UniqueStringSet> de::Visitor<'de> for UniqueSeqVisitor<C> {
+    type Value = C;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a sequence of unique strings")
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut builder = C::builder(capacity(seq.size_hint()));
+        let mut idx = 0;
+
+        while let Some(item) = seq.next_element_seed(TrimStringSeed)? {
+            if let Err((value, first)) = C::insert(&mut builder, item, idx) {
+                return Err(Error::Duplicate {
+                    value,
+                    first,
+                    index: idx,
+                }
+                .into_de_error());
+            }
+
+            idx += 1;
+        }
+
+        Ok(C::finish(builder))
+    }
+}
+
+/// Trims set of strings during deserialization, returning error if any items are duplicates.
+///
+/// Strings are compared _after_ being trimmed. The error message includes both indices of the first
+/// duplicate and its trimmed value.
+#[cfg(feature = "std")]
+pub fn hashset_string_unique<'a, D: Deserializer<'a>>(de: D) -> Result<HashSet<String>, D::Error> {
+    de.deserialize_seq(UniqueSeqVisitor(PhantomData))
+}
+
+/// Trims ordered set of strings during deserialization, returning error if any items are
+/// duplicates.
+///
+/// Strings are compared _after_ being trimmed. The error message includes both indices of the first
+/// duplicate and its trimmed value.
+pub fn btreeset_string_unique<'a, D: Deserializer<'a>>(
+    de: D,
+) -> Result<BTreeSet<String>, D::Error> {
+    de.deserialize_seq(UniqueSeqVisitor(PhantomData))
+}
+
+/// Trims insertion-ordered set of strings during deserialization, returning error if any items are
+/// duplicates.
+///
+/// Strings are compared _after_ being trimmed. The error message includes both indices of the first
+/// duplicate and its trimmed value.
+#[cfg(feature = "indexmap")]
+pub fn indexset_string_unique<'a, D: Deserializer<'a>>(
+    de: D,
+) -> Result<indexmap::IndexSet<String>, D::Error> {
+    de.deserialize_seq(UniqueSeqVisitor(PhantomData))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString as _;
+
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[test]
+    fn btreeset_string_unique() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::btreeset_string_unique")]
+            foo: BTreeSet<String>,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": 1 }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": ["", " "] }"#).unwrap_err();
+
+        let err = serde_json::from_str::<Foo>(r#"{ "foo": ["bar", "baz", "  bar"] }"#).unwrap_err();
+        assert!(err.to_string().starts_with(
+            r#"invalid value: duplicate string "bar" at indices 0 and 2 after trimming"#
+        ));
+
+        assert!(serde_json::from_str::<Foo>(r#"{ "foo": [] }"#)
+            .unwrap()
+            .foo
+            .is_empty());
+        assert!(
+            serde_json::from_str::<Foo>(r#"{ "foo": [" baz", "bar "] }"#)
+                .unwrap()
+                .foo
+                .iter()
+                .eq(["bar", "baz"])
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hashset_string_unique() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::hashset_string_unique")]
+            foo: HashSet<String>,
+        }
+
+        let err =
+            serde_json::from_str::<Foo>(r#"{ "foo": ["a", "bar ", "b", "  bar"] }"#).unwrap_err();
+        assert!(err.to_string().starts_with(
+            r#"invalid value: duplicate string "bar" at indices 1 and 3 after trimming"#
+        ));
+
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": [" bar", "baz "] }"#)
+                .unwrap()
+                .foo,
+            HashSet::from(["bar".to_owned(), "baz".to_owned()]),
+        );
+    }
+
+    #[cfg(feature = "indexmap")]
+    #[test]
+    fn indexset_string_unique() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "super::indexset_string_unique")]
+            foo: indexmap::IndexSet<String>,
+        }
+
+        let err = serde_json::from_str::<Foo>(r#"{ "foo": ["a", "b", " b "] }"#).unwrap_err();
+        assert!(err.to_string().starts_with(
+            r#"invalid value: duplicate string "b" at indices 1 and 2 after trimming"#
+        ));
+
+        assert!(
+            serde_json::from_str::<Foo>(r#"{ "foo": [" baz", "bar "] }"#)
+                .unwrap()
+                .foo
+                .iter()
+                .eq(["baz", "bar"])
+        );
+    }
+}
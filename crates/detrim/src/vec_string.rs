@@ -1,27 +1,62 @@
-use alloc::{borrow::ToOwned as _, string::String, vec::Vec};
+use alloc::{string::String, vec::Vec};
 
 use serde::{Deserialize as _, Deserializer};
 
-/// Trims list of strings during deserialization.
-pub fn vec_string<'a, D: Deserializer<'a>>(de: D) -> Result<Vec<String>, D::Error> {
-    let mut list = Vec::<String>::deserialize(de)?;
+use crate::{
+    seq::{EmptyItems, TrimSeqVisitor},
+    string::trim,
+};
+
+/// Trims list of string slices during deserialization.
+pub fn vec_str<'a, 'de: 'a, D: Deserializer<'de>>(de: D) -> Result<Vec<&'a str>, D::Error> {
+    let mut list = <Vec<&'a str>>::deserialize(de)?;
 
     for item in &mut list {
-        #[allow(clippy::assigning_clones)]
-        {
-            *item = item.trim().to_owned();
-        }
+        *item = trim(item);
     }
 
     Ok(list)
 }
 
+/// Trims list of strings during deserialization.
+pub fn vec_string<'a, D: Deserializer<'a>>(de: D) -> Result<Vec<String>, D::Error> {
+    de.deserialize_seq(TrimSeqVisitor::new(EmptyItems::Keep, false))
+}
+
 #[cfg(test)]
 mod tests {
+    use alloc::string::ToString as _;
+
     use serde::Deserialize;
 
     use super::*;
 
+    #[test]
+    fn vec_str() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo<'a> {
+            #[serde(borrow, deserialize_with = "super::vec_str")]
+            foo: Vec<&'a str>,
+        }
+
+        serde_json::from_str::<Foo<'static>>(r#"{ "foo": 1 }"#).unwrap_err();
+        serde_json::from_str::<Foo<'static>>(r#"{ "foo": "" }"#).unwrap_err();
+
+        // escape sequences cannot be borrowed
+        serde_json::from_str::<Foo<'static>>(r#"{ "foo": [" b\\ar "] }"#).unwrap_err();
+
+        assert!(serde_json::from_str::<Foo<'_>>(r#"{ "foo": [] }"#)
+            .unwrap()
+            .foo
+            .is_empty());
+        assert_eq!(
+            serde_json::from_str::<Foo<'_>>(r#"{ "foo": [" ", " bar", "baz  "] }"#)
+                .unwrap()
+                .foo,
+            ["", "bar", "baz"],
+        );
+    }
+
     #[test]
     fn vec_string() {
         #[derive(Debug, Deserialize, PartialEq, Eq)]
@@ -74,4 +109,105 @@ mod tests {
             serde_json::from_str(r#"{ "foo": ["  bar  "] }"#).unwrap(),
         );
     }
+
+    #[test]
+    fn vec_string_non_empty() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "crate::seq::NonEmpty::<false>::vec_string")]
+            foo: Vec<String>,
+        }
+
+        impl Foo {
+            fn new(foo: impl IntoIterator<Item = impl Into<String>>) -> Self {
+                Self {
+                    foo: foo.into_iter().map(Into::into).collect(),
+                }
+            }
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": 1 }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": [""] }"#).unwrap_err();
+
+        let err = serde_json::from_str::<Foo>(r#"{ "foo": ["bar", "  "] }"#).unwrap_err();
+        assert!(err.to_string().contains("empty string at index 1"));
+
+        assert_eq!(
+            Foo::new([""; 0]),
+            serde_json::from_str(r#"{ "foo": [] }"#).unwrap(),
+        );
+        assert_eq!(
+            Foo::new(["bar"]),
+            serde_json::from_str(r#"{ "foo": ["  bar  "] }"#).unwrap(),
+        );
+        assert_eq!(
+            Foo::new(["bar", "baz"]),
+            serde_json::from_str(r#"{ "foo": [" bar", "baz "] }"#).unwrap(),
+        );
+    }
+
+    #[test]
+    fn vec_string_skip_empty() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "crate::seq::SkipEmpty::<false>::vec_string")]
+            foo: Vec<String>,
+        }
+
+        impl Foo {
+            fn new(foo: impl IntoIterator<Item = impl Into<String>>) -> Self {
+                Self {
+                    foo: foo.into_iter().map(Into::into).collect(),
+                }
+            }
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": 1 }"#).unwrap_err();
+
+        assert_eq!(
+            Foo::new([""; 0]),
+            serde_json::from_str(r#"{ "foo": [] }"#).unwrap(),
+        );
+        assert_eq!(
+            Foo::new([""; 0]),
+            serde_json::from_str(r#"{ "foo": ["", " "] }"#).unwrap(),
+        );
+        assert_eq!(
+            Foo::new(["bar", "baz"]),
+            serde_json::from_str(r#"{ "foo": [" bar", "  ", "baz "] }"#).unwrap(),
+        );
+    }
+
+    #[test]
+    fn vec_string_at_least_one() {
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(deserialize_with = "crate::seq::NonEmpty::<true>::vec_string")]
+            foo: Vec<String>,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq, Eq)]
+        struct Bar {
+            #[serde(deserialize_with = "crate::seq::SkipEmpty::<true>::vec_string")]
+            bar: Vec<String>,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": [] }"#).unwrap_err();
+        serde_json::from_str::<Foo>(r#"{ "foo": [" "] }"#).unwrap_err();
+        assert_eq!(
+            serde_json::from_str::<Foo>(r#"{ "foo": [" bar "] }"#)
+                .unwrap()
+                .foo,
+            ["bar"],
+        );
+
+        serde_json::from_str::<Bar>(r#"{ "bar": [] }"#).unwrap_err();
+        serde_json::from_str::<Bar>(r#"{ "bar": [" ", ""] }"#).unwrap_err();
+        assert_eq!(
+            serde_json::from_str::<Bar>(r#"{ "bar": [" ", " bar "] }"#)
+                .unwrap()
+                .bar,
+            ["bar"],
+        );
+    }
 }
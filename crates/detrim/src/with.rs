@@ -0,0 +1,327 @@
+//! Modules for use with `#[serde(with = "...")]`.
+//!
+//! Each module pairs a trimming `deserialize` function with a `serialize` function that trims on
+//! the way out, so that trimming is applied symmetrically.
+//!
+//! # Examples
+//!
+//! ```
+//! #[derive(Debug, serde::Serialize, serde::Deserialize)]
+//! struct Form {
+//!     #[serde(with = "detrim::with::string")]
+//!     name: String,
+//! }
+//!
+//! let form = serde_json::from_str::<Form>(r#"{ "name": "  ferris   " }"#).unwrap();
+//! assert_eq!(form.name, "ferris");
+//!
+//! let form = Form { name: "  ferris ".to_owned() };
+//! assert_eq!(serde_json::to_string(&form).unwrap(), r#"{"name":"ferris"}"#);
+//! ```
+
+/// Trimming (de)serialization of `String`s.
+pub mod string {
+    use serde::Serializer;
+
+    pub use crate::string::string as deserialize;
+
+    /// Trims a string during serialization.
+    pub fn serialize<S: Serializer>(val: &str, ser: S) -> Result<S::Ok, S::Error> {
+        ser.serialize_str(val.trim())
+    }
+}
+
+/// Trimming (de)serialization of `Option<String>`s.
+pub mod option_string {
+    use alloc::string::String;
+
+    use serde::Serializer;
+
+    pub use crate::string::option_string as deserialize;
+
+    /// Trims an optional string during serialization.
+    pub fn serialize<S: Serializer>(val: &Option<String>, ser: S) -> Result<S::Ok, S::Error> {
+        match val {
+            Some(val) => ser.serialize_some(val.trim()),
+            None => ser.serialize_none(),
+        }
+    }
+}
+
+/// Trimming (de)serialization of `Vec<String>`s.
+pub mod vec_string {
+    use alloc::string::String;
+
+    use serde::Serializer;
+
+    pub use crate::vec_string::vec_string as deserialize;
+
+    /// Trims list of strings during serialization.
+    pub fn serialize<S: Serializer>(val: &[String], ser: S) -> Result<S::Ok, S::Error> {
+        ser.collect_seq(val.iter().map(|item| item.trim()))
+    }
+}
+
+/// Trimming (de)serialization of `HashSet<String>`s.
+#[cfg(feature = "std")]
+pub mod hashset_string {
+    use std::{collections::HashSet, string::String};
+
+    use serde::Serializer;
+
+    pub use crate::hashset_string::hashset_string as deserialize;
+
+    /// Trims set of strings during serialization.
+    ///
+    /// Strings are deduplicated _after_ being trimmed, matching the behavior of
+    /// [`deserialize`](crate::hashset_string()).
+    pub fn serialize<S: Serializer>(val: &HashSet<String>, ser: S) -> Result<S::Ok, S::Error> {
+        let set = val.iter().map(|item| item.trim()).collect::<HashSet<_>>();
+        ser.collect_seq(set)
+    }
+}
+
+/// Trimming (de)serialization of `BTreeSet<String>`s.
+pub mod btreeset_string {
+    use alloc::{collections::BTreeSet, string::String};
+
+    use serde::Serializer;
+
+    pub use crate::btreeset_string::btreeset_string as deserialize;
+
+    /// Trims set of strings during serialization.
+    ///
+    /// Strings are deduplicated _after_ being trimmed, matching the behavior of
+    /// [`deserialize`](crate::btreeset_string()).
+    pub fn serialize<S: Serializer>(val: &BTreeSet<String>, ser: S) -> Result<S::Ok, S::Error> {
+        let set = val.iter().map(|item| item.trim()).collect::<BTreeSet<_>>();
+        ser.collect_seq(set)
+    }
+}
+
+/// Trimming (de)serialization of `Cow<str>`s.
+pub mod cow_str {
+    use serde::Serializer;
+
+    pub use crate::cow_str::cow_str as deserialize;
+
+    /// Trims a CoW string during serialization.
+    pub fn serialize<S: Serializer>(val: &str, ser: S) -> Result<S::Ok, S::Error> {
+        ser.serialize_str(val.trim())
+    }
+}
+
+/// Trimming (de)serialization of non-empty `String`s.
+pub mod string_non_empty {
+    use serde::{ser, Serializer};
+
+    pub use crate::string_non_empty::string_non_empty as deserialize;
+
+    /// Trims string during serialization, returning error if it ends up empty.
+    pub fn serialize<S: Serializer>(val: &str, ser: S) -> Result<S::Ok, S::Error> {
+        match val.trim() {
+            "" => Err(ser::Error::custom("expected non-empty string")),
+            val => ser.serialize_str(val),
+        }
+    }
+}
+
+/// Trimming (de)serialization of `Option<String>`s where empty strings are `None`.
+pub mod option_string_non_empty {
+    use alloc::string::String;
+
+    use serde::Serializer;
+
+    pub use crate::string_non_empty::option_string_non_empty as deserialize;
+
+    /// Trims string during serialization, serializing `None` if it ends up empty.
+    pub fn serialize<S: Serializer>(val: &Option<String>, ser: S) -> Result<S::Ok, S::Error> {
+        match val.as_deref().map(str::trim) {
+            None | Some("") => ser.serialize_none(),
+            Some(val) => ser.serialize_some(val),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{
+        borrow::{Cow, ToOwned as _},
+        string::String,
+        vec,
+        vec::Vec,
+    };
+
+    use serde::{Deserialize, Serialize};
+
+    #[test]
+    fn string() {
+        #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(with = "super::string")]
+            foo: String,
+        }
+
+        let foo = serde_json::from_str::<Foo>(r#"{ "foo": "  bar  " }"#).unwrap();
+        assert_eq!(foo.foo, "bar");
+
+        let foo = Foo {
+            foo: " bar ".to_owned(),
+        };
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"foo":"bar"}"#);
+    }
+
+    #[test]
+    fn option_string() {
+        #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(with = "super::option_string")]
+            foo: Option<String>,
+        }
+
+        let foo = serde_json::from_str::<Foo>(r#"{ "foo": "  bar  " }"#).unwrap();
+        assert_eq!(foo.foo.as_deref(), Some("bar"));
+
+        let foo = Foo { foo: None };
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"foo":null}"#);
+
+        let foo = Foo {
+            foo: Some(" ".to_owned()),
+        };
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"foo":""}"#);
+
+        let foo = Foo {
+            foo: Some(" bar ".to_owned()),
+        };
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"foo":"bar"}"#);
+    }
+
+    #[test]
+    fn vec_string() {
+        #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(with = "super::vec_string")]
+            foo: Vec<String>,
+        }
+
+        let foo = serde_json::from_str::<Foo>(r#"{ "foo": [" bar", "baz "] }"#).unwrap();
+        assert_eq!(foo.foo, ["bar", "baz"]);
+
+        let foo = Foo { foo: vec![] };
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"foo":[]}"#);
+
+        let foo = Foo {
+            foo: vec![" bar".to_owned(), "baz ".to_owned()],
+        };
+        assert_eq!(
+            serde_json::to_string(&foo).unwrap(),
+            r#"{"foo":["bar","baz"]}"#
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hashset_string() {
+        use std::collections::HashSet;
+
+        #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(with = "super::hashset_string")]
+            foo: HashSet<String>,
+        }
+
+        let foo = serde_json::from_str::<Foo>(r#"{ "foo": [" bar", "bar "] }"#).unwrap();
+        assert_eq!(foo.foo, HashSet::from(["bar".to_owned()]));
+
+        let foo = Foo {
+            foo: HashSet::from([" bar".to_owned(), "bar ".to_owned()]),
+        };
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"foo":["bar"]}"#);
+    }
+
+    #[test]
+    fn btreeset_string() {
+        use alloc::collections::BTreeSet;
+
+        #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(with = "super::btreeset_string")]
+            foo: BTreeSet<String>,
+        }
+
+        let foo = serde_json::from_str::<Foo>(r#"{ "foo": [" bar", "bar "] }"#).unwrap();
+        assert_eq!(foo.foo, BTreeSet::from(["bar".to_owned()]));
+
+        let foo = Foo {
+            foo: BTreeSet::from([" bar".to_owned(), "bar ".to_owned(), "baz".to_owned()]),
+        };
+        assert_eq!(
+            serde_json::to_string(&foo).unwrap(),
+            r#"{"foo":["bar","baz"]}"#
+        );
+    }
+
+    #[test]
+    fn cow_str() {
+        #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+        struct Foo<'a> {
+            #[serde(borrow, with = "super::cow_str")]
+            foo: Cow<'a, str>,
+        }
+
+        let source = br#"{ "foo": " bar " }"#.to_vec();
+        let foo = serde_json::from_slice::<Foo<'_>>(&source).unwrap();
+        assert!(matches!(&foo.foo, Cow::Borrowed("bar")));
+
+        let foo = Foo {
+            foo: Cow::Borrowed(" bar "),
+        };
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"foo":"bar"}"#);
+    }
+
+    #[test]
+    fn string_non_empty() {
+        #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(with = "super::string_non_empty")]
+            foo: String,
+        }
+
+        serde_json::from_str::<Foo>(r#"{ "foo": "  " }"#).unwrap_err();
+
+        let foo = serde_json::from_str::<Foo>(r#"{ "foo": "  bar  " }"#).unwrap();
+        assert_eq!(foo.foo, "bar");
+
+        let foo = Foo {
+            foo: " ".to_owned(),
+        };
+        serde_json::to_string(&foo).unwrap_err();
+
+        let foo = Foo {
+            foo: " bar ".to_owned(),
+        };
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"foo":"bar"}"#);
+    }
+
+    #[test]
+    fn option_string_non_empty() {
+        #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+        struct Foo {
+            #[serde(with = "super::option_string_non_empty")]
+            foo: Option<String>,
+        }
+
+        let foo = serde_json::from_str::<Foo>(r#"{ "foo": "  " }"#).unwrap();
+        assert_eq!(foo.foo, None);
+
+        let foo = Foo {
+            foo: Some(" ".to_owned()),
+        };
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"foo":null}"#);
+
+        let foo = Foo {
+            foo: Some(" bar ".to_owned()),
+        };
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"foo":"bar"}"#);
+    }
+}
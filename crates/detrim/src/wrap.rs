@@ -0,0 +1,443 @@
+//! Deserializer wrapper shared by [`observe::deserialize()`](crate::observe::deserialize) and
+//! [`error::deserialize()`](crate::error::deserialize).
+//!
+//! The wrapper forwards every call to the wrapped deserializer and wraps each nested visitor,
+//! seed, and accessor in turn, so that it stays in place for the whole input. What it does on the
+//! way is decided by a [`Layer`].
+
+use std::{
+    fmt,
+    marker::PhantomData,
+    string::{String, ToString as _},
+    vec::Vec,
+};
+
+use serde::{de, Deserializer};
+
+/// Single step in a [`Path`](crate::observe::Path).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Segment {
+    /// Map key, struct field, or enum variant.
+    Field(String),
+
+    /// Sequence index.
+    Index(usize),
+
+    /// Map key which could not be represented as a string.
+    Unknown,
+}
+
+/// Behavior of a [`Wrap`]ped deserializer.
+pub(crate) trait Layer {
+    /// Error type seen by values deserialized through the wrapper.
+    type Error<E: de::Error>: de::Error;
+
+    /// Whether map keys and enum variants are captured, to be passed to [`Layer::with_segment()`].
+    const TRACK_PATHS: bool;
+
+    /// Converts the result of calling into the wrapped deserializer.
+    fn lift<T, E: de::Error>(res: Result<T, E>) -> Result<T, Self::Error<E>>;
+
+    /// Converts an error to pass it back through the wrapped deserializer.
+    fn lower<E: de::Error>(err: Self::Error<E>) -> E;
+
+    /// Calls `f` to deserialize the value found at `segment`.
+    fn with_segment<R>(segment: Segment, f: impl FnOnce() -> R) -> R {
+        let _ = segment;
+        f()
+    }
+}
+
+/// Deserializer wrapper which applies `L` to the wrapped deserializer and everything nested in it.
+///
+/// When `key` is set, the deserialized value is a map key or enum variant and its string form is
+/// captured.
+pub(crate) struct Wrap<'k, D, L> {
+    de: D,
+    key: Option<&'k mut Option<Segment>>,
+    layer: PhantomData<L>,
+}
+
+impl<'k, D, L> Wrap<'k, D, L> {
+    pub(crate) fn new(de: D) -> Self {
+        Self::with_key(de, None)
+    }
+
+    fn with_key(de: D, key: Option<&'k mut Option<Segment>>) -> Self {
+        Self {
+            de,
+            key,
+            layer: PhantomData,
+        }
+    }
+
+    fn wrap<V>(self, visitor: V) -> (D, WrapVisitor<'k, V, L>) {
+        (self.de, WrapVisitor::with_key(visitor, self.key))
+    }
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method<V: de::Visitor<'de>>(
+                self,
+                $($arg: $ty,)*
+                visitor: V,
+            ) -> Result<V::Value, Self::Error> {
+                let (de, visitor) = self.wrap(visitor);
+                L::lift(de.$method($($arg,)* visitor))
+            }
+        )*
+    };
+}
+
+impl<'de, D: Deserializer<'de>, L: Layer> Deserializer<'de> for Wrap<'_, D, L> {
+    type Error = L::Error<D::Error>;
+
+    forward_deserialize! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_i128();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_u128();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_option();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_ignored_any();
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.de.is_human_readable()
+    }
+}
+
+/// Visitor wrapper which captures keys and wraps nested deserializers and accessors.
+struct WrapVisitor<'k, V, L> {
+    visitor: V,
+    key: Option<&'k mut Option<Segment>>,
+    layer: PhantomData<L>,
+}
+
+impl<'k, V, L> WrapVisitor<'k, V, L> {
+    fn new(visitor: V) -> Self {
+        Self::with_key(visitor, None)
+    }
+
+    fn with_key(visitor: V, key: Option<&'k mut Option<Segment>>) -> Self {
+        Self {
+            visitor,
+            key,
+            layer: PhantomData,
+        }
+    }
+
+    fn capture(&mut self, segment: impl FnOnce() -> Segment) {
+        if let Some(key) = &mut self.key {
+            **key = Some(segment());
+        }
+    }
+}
+
+macro_rules! forward_visit {
+    ($($method:ident($ty:ty) => $segment:expr;)*) => {
+        $(
+            fn $method<E: de::Error>(mut self, val: $ty) -> Result<Self::Value, E> {
+                self.capture(|| $segment(&val));
+                self.visitor.$method::<L::Error<E>>(val).map_err(L::lower)
+            }
+        )*
+    };
+    ($($method:ident();)*) => {
+        $(
+            fn $method<E: de::Error>(self) -> Result<Self::Value, E> {
+                self.visitor.$method::<L::Error<E>>().map_err(L::lower)
+            }
+        )*
+    };
+}
+
+impl<'de, V: de::Visitor<'de>, L: Layer> de::Visitor<'de> for WrapVisitor<'_, V, L> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.visitor.expecting(formatter)
+    }
+
+    forward_visit! {
+        visit_bool(bool) => field_segment;
+        visit_i8(i8) => field_segment;
+        visit_i16(i16) => field_segment;
+        visit_i32(i32) => field_segment;
+        visit_i64(i64) => field_segment;
+        visit_i128(i128) => field_segment;
+        visit_u8(u8) => field_segment;
+        visit_u16(u16) => field_segment;
+        visit_u32(u32) => field_segment;
+        visit_u64(u64) => field_segment;
+        visit_u128(u128) => field_segment;
+        visit_f32(f32) => field_segment;
+        visit_f64(f64) => field_segment;
+        visit_char(char) => field_segment;
+        visit_str(&str) => field_segment;
+        visit_borrowed_str(&'de str) => field_segment;
+        visit_string(String) => field_segment;
+        visit_bytes(&[u8]) => bytes_segment;
+        visit_borrowed_bytes(&'de [u8]) => bytes_segment;
+        visit_byte_buf(Vec<u8>) => bytes_segment;
+    }
+
+    forward_visit! {
+        visit_none();
+        visit_unit();
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        self.visitor
+            .visit_some(Wrap::<_, L>::new(de))
+            .map_err(L::lower)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        self.visitor
+            .visit_newtype_struct(Wrap::<_, L>::new(de))
+            .map_err(L::lower)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        self.visitor
+            .visit_seq(WrapSeqAccess::<_, L> {
+                seq,
+                idx: 0,
+                layer: PhantomData,
+            })
+            .map_err(L::lower)
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        self.visitor
+            .visit_map(WrapMapAccess::<_, L> {
+                map,
+                key: None,
+                layer: PhantomData,
+            })
+            .map_err(L::lower)
+    }
+
+    fn visit_enum<A: de::EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        self.visitor
+            .visit_enum(WrapEnumAccess::<_, L> {
+                data,
+                layer: PhantomData,
+            })
+            .map_err(L::lower)
+    }
+}
+
+fn field_segment(val: &impl fmt::Display) -> Segment {
+    Segment::Field(val.to_string())
+}
+
+fn bytes_segment(val: &impl AsRef<[u8]>) -> Segment {
+    match std::str::from_utf8(val.as_ref()) {
+        Ok(val) => Segment::Field(val.to_owned()),
+        Err(_) => Segment::Unknown,
+    }
+}
+
+/// Seed wrapper which deserializes through a [`Wrap`].
+struct WrapSeed<'k, S, L> {
+    seed: S,
+    key: Option<&'k mut Option<Segment>>,
+    layer: PhantomData<L>,
+}
+
+impl<'k, S, L: Layer> WrapSeed<'k, S, L> {
+    fn new(seed: S) -> Self {
+        Self::with_key(seed, None)
+    }
+
+    /// Captures the key into `key`, if paths are tracked.
+    fn with_key(seed: S, key: Option<&'k mut Option<Segment>>) -> Self {
+        Self {
+            seed,
+            key: key.filter(|_| L::TRACK_PATHS),
+            layer: PhantomData,
+        }
+    }
+}
+
+impl<'de, S: de::DeserializeSeed<'de>, L: Layer> de::DeserializeSeed<'de> for WrapSeed<'_, S, L> {
+    type Value = S::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        self.seed
+            .deserialize(Wrap::<_, L>::with_key(de, self.key))
+            .map_err(L::lower)
+    }
+}
+
+struct WrapSeqAccess<A, L> {
+    seq: A,
+    idx: usize,
+    layer: PhantomData<L>,
+}
+
+impl<'de, A: de::SeqAccess<'de>, L: Layer> de::SeqAccess<'de> for WrapSeqAccess<A, L> {
+    type Error = L::Error<A::Error>;
+
+    fn next_element_seed<S: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> Result<Option<S::Value>, Self::Error> {
+        let idx = self.idx;
+        self.idx += 1;
+
+        L::with_segment(Segment::Index(idx), || {
+            L::lift(self.seq.next_element_seed(WrapSeed::<_, L>::new(seed)))
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.seq.size_hint()
+    }
+}
+
+struct WrapMapAccess<A, L> {
+    map: A,
+    key: Option<Segment>,
+    layer: PhantomData<L>,
+}
+
+impl<'de, A: de::MapAccess<'de>, L: Layer> de::MapAccess<'de> for WrapMapAccess<A, L> {
+    type Error = L::Error<A::Error>;
+
+    fn next_key_seed<S: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> Result<Option<S::Value>, Self::Error> {
+        self.key = None;
+
+        L::lift(
+            self.map
+                .next_key_seed(WrapSeed::<_, L>::with_key(seed, Some(&mut self.key))),
+        )
+    }
+
+    fn next_value_seed<S: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> Result<S::Value, Self::Error> {
+        let segment = self.key.take().unwrap_or(Segment::Unknown);
+
+        L::with_segment(segment, || {
+            L::lift(self.map.next_value_seed(WrapSeed::<_, L>::new(seed)))
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.map.size_hint()
+    }
+}
+
+struct WrapEnumAccess<A, L> {
+    data: A,
+    layer: PhantomData<L>,
+}
+
+impl<'de, A: de::EnumAccess<'de>, L: Layer> de::EnumAccess<'de> for WrapEnumAccess<A, L> {
+    type Error = L::Error<A::Error>;
+    type Variant = WrapVariantAccess<A::Variant, L>;
+
+    fn variant_seed<S: de::DeserializeSeed<'de>>(
+        self,
+        seed: S,
+    ) -> Result<(S::Value, Self::Variant), Self::Error> {
+        let mut variant = None;
+
+        let (val, access) = L::lift(
+            self.data
+                .variant_seed(WrapSeed::<_, L>::with_key(seed, Some(&mut variant))),
+        )?;
+
+        Ok((
+            val,
+            WrapVariantAccess {
+                access,
+                variant: variant.unwrap_or(Segment::Unknown),
+                layer: PhantomData,
+            },
+        ))
+    }
+}
+
+struct WrapVariantAccess<A, L> {
+    access: A,
+    variant: Segment,
+    layer: PhantomData<L>,
+}
+
+impl<'de, A: de::VariantAccess<'de>, L: Layer> de::VariantAccess<'de> for WrapVariantAccess<A, L> {
+    type Error = L::Error<A::Error>;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        L::lift(self.access.unit_variant())
+    }
+
+    fn newtype_variant_seed<S: de::DeserializeSeed<'de>>(
+        self,
+        seed: S,
+    ) -> Result<S::Value, Self::Error> {
+        let access = self.access;
+
+        L::with_segment(self.variant, || {
+            L::lift(access.newtype_variant_seed(WrapSeed::<_, L>::new(seed)))
+        })
+    }
+
+    fn tuple_variant<V: de::Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let access = self.access;
+
+        L::with_segment(self.variant, || {
+            L::lift(access.tuple_variant(len, WrapVisitor::<_, L>::new(visitor)))
+        })
+    }
+
+    fn struct_variant<V: de::Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let access = self.access;
+
+        L::with_segment(self.variant, || {
+            L::lift(access.struct_variant(fields, WrapVisitor::<_, L>::new(visitor)))
+        })
+    }
+}